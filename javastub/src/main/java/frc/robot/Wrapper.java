@@ -7,6 +7,7 @@ import com.revrobotics.CANSparkMax;
 import com.revrobotics.CANSparkLowLevel.MotorType;
 import static com.revrobotics.CANSparkMax.IdleMode;
 import com.revrobotics.CANSparkFlex;
+import com.revrobotics.SparkPIDController.ArbFFUnits;
 import com.ctre.phoenix6.StatusSignal;
 import static com.revrobotics.CANSparkMax.ControlType;
 
@@ -20,18 +21,25 @@ import edu.wpi.first.wpilibj.smartdashboard.Field2d;
 import edu.wpi.first.wpilibj.smartdashboard.SendableChooser;
 import edu.wpi.first.wpilibj.smartdashboard.SmartDashboard;
 import edu.wpi.first.wpilibj.GenericHID.RumbleType;
+import edu.wpi.first.wpilibj.PowerDistribution;
+import edu.wpi.first.wpilibj.RobotController;
+import edu.wpi.first.wpilibj.Timer;
 import org.photonvision.PhotonCamera;
 import org.photonvision.PhotonPoseEstimator;
 import org.photonvision.PhotonPoseEstimator.PoseStrategy;
 import org.photonvision.EstimatedRobotPose;
+import org.photonvision.targeting.PhotonTrackedTarget;
 
 import java.io.OutputStream;
 import java.net.HttpURLConnection;
 import java.net.URLConnection;
 import java.nio.charset.StandardCharsets;
+import java.util.HashMap;
+import java.util.Map;
 import java.util.Optional;
 import java.net.URL;
 
+import com.ctre.phoenix6.hardware.CANcoder;
 import com.ctre.phoenix6.hardware.TalonFX;
 
 import static edu.wpi.first.wpilibj.RobotBase.isReal;
@@ -54,6 +62,12 @@ public class Wrapper {
     public static CANSparkFlex createSparkFlex(int id) { return new CANSparkFlex(id, MotorType.kBrushless); }
 
     public static void sparkFollow(CANSparkMax leader, CANSparkMax follower, boolean invert) { follower.follow(leader, invert); }
+    public static int sparkFaults(CANSparkMax spark) { return spark.getFaults(); }
+    public static int sparkLastError(CANSparkMax spark) { return spark.getLastError().value; }
+    public static double sparkTemperature(CANSparkMax spark) { return spark.getMotorTemperature(); }
+    public static void sparkSetPositionFF(CANSparkMax spark, double position, double arbFF) {
+        spark.getPIDController().setReference(position, ControlType.kPosition, 0, arbFF, ArbFFUnits.kVoltage);
+    }
 
     public static int getAllianceStation() {
         AllianceStationID allianceID = DriverStationJNI.getAllianceStation();
@@ -75,6 +89,26 @@ public class Wrapper {
         }
     }
 
+    public static double batteryVoltage() { return RobotController.getBatteryVoltage(); }
+    public static boolean isBrownedOut() { return RobotController.isBrownedOut(); }
+
+    private static PowerDistribution pdh;
+
+    /// made on first use, after the HAL is up
+    private static PowerDistribution pdh() {
+        if (pdh == null) {
+            pdh = new PowerDistribution();
+        }
+        return pdh;
+    }
+
+    public static double[] pdhCurrents() { return pdh().getAllCurrents(); }
+    public static double pdhVoltage() { return pdh().getVoltage(); }
+    public static double pdhTotalCurrent() { return pdh().getTotalCurrent(); }
+    public static double pdhTemperature() { return pdh().getTemperature(); }
+    public static double pdhTotalEnergy() { return pdh().getTotalEnergy(); }
+    public static void pdhResetTotalEnergy() { pdh().resetTotalEnergy(); }
+
     public static void startNetworkTables() {
         NetworkTableInstance inst = NetworkTableInstance.getDefault();
         if (isReal()) {
@@ -84,14 +118,28 @@ public class Wrapper {
         }
     }
 
+    private static AHRS navx;
+
+    /// frcrs gets its navx from here, keeping it lets rust reach readings frcrs doesn't bind
     public static AHRS createAHRS() {
-        return new AHRS();
+        if (navx == null) {
+            navx = new AHRS();
+        }
+        return navx;
     }
 
     public static double getAngle(AHRS navx) {
         return navx.getAngle();
     }
 
+    public static double navxRoll() {
+        return createAHRS().getRoll();
+    }
+
+    public static boolean navxConnected() {
+        return createAHRS().isConnected();
+    }
+
     public static ControlMode ctreVelocity() {
         return ControlMode.Velocity;
     }
@@ -120,6 +168,32 @@ public class Wrapper {
         return RumbleType.kRightRumble;
     }
 
+    /// phoenix 6 lets a second object address the same device, so rust only needs the id
+    private static final Map<Integer, TalonFX> talons = new HashMap<>();
+    private static final Map<Integer, CANcoder> cancoders = new HashMap<>();
+
+    private static TalonFX talon(int id) {
+        return talons.computeIfAbsent(id, key -> new TalonFX(key, "can0"));
+    }
+
+    private static CANcoder cancoder(int id) {
+        return cancoders.computeIfAbsent(id, key -> new CANcoder(key, "can0"));
+    }
+
+    public static void talonCurrentLimit(int id, double amps) {
+        talon(id).getConfigurator().apply(new CurrentLimitsConfigs()
+                .withStatorCurrentLimit(amps)
+                .withStatorCurrentLimitEnable(true));
+    }
+
+    public static boolean talonConnected(int id) { return talon(id).getDeviceTemp().refresh().getStatus().isOK(); }
+    public static int talonFaults(int id) { return talon(id).getFaultField().refresh().getValue(); }
+    public static double talonTemperature(int id) { return talon(id).getDeviceTemp().refresh().getValue(); }
+    public static double talonCurrent(int id) { return talon(id).getStatorCurrent().refresh().getValue(); }
+
+    public static boolean cancoderConnected(int id) { return cancoder(id).getSupplyVoltage().refresh().getStatus().isOK(); }
+    public static int cancoderFaults(int id) { return cancoder(id).getFaultField().refresh().getValue(); }
+
     public static double getValue(StatusSignal<Double> holder) {
         return holder.getValue();
     }
@@ -157,6 +231,10 @@ public class Wrapper {
 
     static Pose2d prevEstimatedRobotPose = new Pose2d();
 
+    /// standard deviations of x, y (meters) and theta (degrees) from one tag at no distance, more tags trust it more
+    static final double[] SINGLE_TAG_STD_DEVS = {0.9, 0.9, 45.};
+    static final double[] MULTI_TAG_STD_DEVS = {0.3, 0.3, 15.};
+
     public static void updateVisionOdo() {
         //photonPoseEstimator.setReferencePose(prevEstimatedRobotPose);
         Optional<EstimatedRobotPose> robotPose =  photonPoseEstimator.update();
@@ -164,14 +242,37 @@ public class Wrapper {
         if (robotPose.isPresent()) {
             try {
                 // URL to which you want to send the POST request
-                URL url = new URL("http://10.25.2.2:5807/set_position");
+                URL url = new URL("http://10.25.2.2:5807/vision");
 
                 //prevEstimatedRobotPose = robotPose.get().estimatedPose.toPose2d();
 
+                EstimatedRobotPose estimate = robotPose.get();
+                StringBuilder tags = new StringBuilder();
+                double totalDistance = 0;
+                for (PhotonTrackedTarget target : estimate.targetsUsed) {
+                    double distance = target.getBestCameraToTarget().getTranslation().getNorm();
+                    totalDistance += distance;
+
+                    if (tags.length() > 0) tags.append(", ");
+                    tags.append("{\"id\": " + target.getFiducialId() +
+                            ", \"distance\": " + distance +
+                            ", \"ambiguity\": " + Math.max(target.getPoseAmbiguity(), 0) + "}");
+                }
+
+                int count = estimate.targetsUsed.size();
+                double[] base = count > 1 ? MULTI_TAG_STD_DEVS : SINGLE_TAG_STD_DEVS;
+                double averageDistance = count > 0 ? totalDistance / count : 0;
+                double scale = 1 + averageDistance * averageDistance / 30;
+
                 // JSON data to be sent in the request body
-                String jsonData = "{\"x\": " + robotPose.get().estimatedPose.getX() +
-                        ", \"y\": " + robotPose.get().estimatedPose.getY() + ", \"theta\": " +
-                        (robotPose.get().estimatedPose.getRotation().getZ() + Math.PI) + "}";
+                String jsonData = "{\"sent\": " + Timer.getFPGATimestamp() +
+                        ", \"observations\": [{\"camera\": \"" + cam.getName() +
+                        "\", \"captured\": " + estimate.timestampSeconds +
+                        ", \"tags\": [" + tags + "]" +
+                        ", \"pose\": {\"x\": " + estimate.estimatedPose.getX() +
+                        ", \"y\": " + estimate.estimatedPose.getY() +
+                        ", \"theta\": " + Math.toDegrees(estimate.estimatedPose.getRotation().getZ() + Math.PI) +
+                        ", \"std_devs\": [" + base[0] * scale + ", " + base[1] * scale + ", " + base[2] * scale + "]}}]}";
 
                 System.out.println(jsonData);
 
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use nalgebra::Vector2;
+use uom::si::{angle::degree, f64::Angle};
+
+use crate::{
+    constants::drivetrain::{AIM_KP, AIM_MAX_ACCELERATION, AIM_MAX_VELOCITY, SHOOTER_FACING},
+    profile::{Constraints, TrapezoidProfile},
+};
+
+/// wrap to -180..180 degrees
+pub fn wrap(angle: Angle) -> Angle {
+    let mut difference = (angle.get::<degree>() + 180.) % 360. - 180.;
+    if difference < -180. {
+        difference += 360.
+    };
+
+    Angle::new::<degree>(difference)
+}
+
+/// Heading (clockwise, same as the navx) that points the shooter at `target`
+pub fn heading_to(position: Vector2<f64>, target: Vector2<f64>) -> Angle {
+    let delta = target - position;
+    let bearing = delta.y.atan2(delta.x).to_degrees(); // counterclockwise
+
+    wrap(Angle::new::<degree>(-(bearing + SHOOTER_FACING)))
+}
+
+/// Holds a heading, with the setpoint limited to a max angular velocity and acceleration
+pub struct HeadingController {
+    /// degrees
+    profile: TrapezoidProfile,
+}
+
+impl Default for HeadingController {
+    fn default() -> Self {
+        Self {
+            profile: TrapezoidProfile::new(Constraints::new(
+                AIM_MAX_VELOCITY,
+                AIM_MAX_ACCELERATION,
+            )),
+        }
+    }
+}
+
+impl HeadingController {
+    pub fn reset(&mut self) {
+        self.profile.reset();
+    }
+
+    /// rotation output for set_speeds, from -1 to 1
+    pub fn calculate(&mut self, measured: Angle, goal: Angle, dt: &Duration) -> f64 {
+        let measured = measured.get::<degree>();
+        let setpoint = self
+            .profile
+            .state()
+            .map_or(measured, |state| state.position);
+
+        // take the short way around
+        let goal =
+            setpoint + wrap(Angle::new::<degree>(goal.get::<degree>() - setpoint)).get::<degree>();
+        let setpoint = self.profile.step(measured, goal, dt);
+
+        let error = wrap(Angle::new::<degree>(measured - setpoint.position));
+        (-error.get::<degree>().to_radians() * AIM_KP).clamp(-1., 1.)
+    }
+}
@@ -0,0 +1,15 @@
+use nalgebra::Vector2;
+
+use crate::{
+    constants::field::{SPEAKER_X, SPEAKER_Y},
+    field::AllianceFlip,
+};
+
+pub mod heading;
+pub mod moving;
+pub mod shot_map;
+
+/// speaker position in odometry coordinates for our alliance
+pub fn speaker() -> Vector2<f64> {
+    AllianceFlip::current().position(Vector2::new(SPEAKER_X, SPEAKER_Y))
+}
@@ -0,0 +1,167 @@
+use nalgebra::Vector2;
+
+use super::shot_map::ShotMap;
+
+const ITERATIONS: usize = 20;
+/// meters, close enough to stop iterating
+const CONVERGED: f64 = 0.001;
+
+#[derive(Clone, Copy, Debug)]
+pub struct MovingShot {
+    /// point to aim at so the robot's velocity carries the note onto the real target
+    pub target: Vector2<f64>,
+    /// distance to look up in the shot map
+    pub distance: f64,
+    /// seconds from leaving the shooter to reaching the target
+    pub flight_time: f64,
+}
+
+/// Find the virtual target for shooting at `target` while moving at `velocity`
+///
+/// `note_speed` gives the horizontal speed the note leaves the shooter at,
+/// relative to the robot, for a shot of the given distance
+pub fn solve<F>(
+    position: Vector2<f64>,
+    velocity: Vector2<f64>,
+    target: Vector2<f64>,
+    note_speed: F,
+) -> MovingShot
+where
+    F: Fn(f64) -> f64,
+{
+    let mut virtual_target = target;
+    let mut flight_time = 0.;
+
+    for _ in 0..ITERATIONS {
+        let distance = (virtual_target - position).magnitude();
+        let speed = note_speed(distance);
+        if speed <= 0. {
+            break;
+        }
+
+        flight_time = distance / speed;
+        let next = target - velocity * flight_time;
+        let change = (next - virtual_target).magnitude();
+        virtual_target = next;
+
+        if change < CONVERGED {
+            break;
+        }
+    }
+
+    MovingShot {
+        target: virtual_target,
+        distance: (virtual_target - position).magnitude(),
+        flight_time,
+    }
+}
+
+/// Moving shot at `target` using the flywheel speeds from the shot map
+pub fn solve_with_map(
+    position: Vector2<f64>,
+    velocity: Vector2<f64>,
+    target: Vector2<f64>,
+    shot_map: &ShotMap,
+) -> MovingShot {
+    solve(position, velocity, target, |distance| {
+        shot_map
+            .get(distance)
+            .map(|shot| shot.exit_speed())
+            .unwrap_or(0.)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector2;
+
+    use super::solve;
+
+    /// where the note ends up when aimed at the solved target
+    fn landing(
+        position: Vector2<f64>,
+        velocity: Vector2<f64>,
+        target: Vector2<f64>,
+        speed: impl Fn(f64) -> f64,
+    ) -> Vector2<f64> {
+        let shot = solve(position, velocity, target, &speed);
+        let direction = (shot.target - position).normalize();
+        let note_velocity = velocity + direction * speed(shot.distance);
+
+        position + note_velocity * shot.flight_time
+    }
+
+    #[test]
+    fn stationary() {
+        let position = Vector2::new(2., 4.);
+        let target = Vector2::new(0., 5.5);
+
+        let shot = solve(position, Vector2::zeros(), target, |_| 7.);
+
+        assert_eq!(shot.target, target);
+        assert!((shot.distance - (target - position).magnitude()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn driving_away_shoots_further() {
+        let position = Vector2::new(2., 5.5);
+        let target = Vector2::new(0., 5.5);
+
+        let shot = solve(position, Vector2::new(1.5, 0.), target, |_| 7.);
+
+        assert!(shot.distance > 2.);
+        assert!(shot.target.x < 0.);
+    }
+
+    #[test]
+    fn strafing_leads_opposite() {
+        let position = Vector2::new(2., 5.5);
+        let target = Vector2::new(0., 5.5);
+
+        let shot = solve(position, Vector2::new(0., 2.), target, |_| 7.);
+
+        assert!(shot.target.y < target.y);
+    }
+
+    #[test]
+    fn lands_on_target_constant_speed() {
+        let position = Vector2::new(3., 3.);
+        let target = Vector2::new(0., 5.5);
+
+        for vx in -3..=3 {
+            for vy in -3..=3 {
+                let velocity = Vector2::new(vx as f64, vy as f64);
+                let landed = landing(position, velocity, target, |_| 8.);
+
+                assert!(
+                    (landed - target).magnitude() < 0.01,
+                    "missed by {} at {:?}",
+                    (landed - target).magnitude(),
+                    velocity
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn lands_on_target_distance_dependent_speed() {
+        let position = Vector2::new(2.5, 4.);
+        let target = Vector2::new(0., 5.5);
+        // further shots spin slower, like the shot map
+        let speed = |distance: f64| 10. - distance * 0.5;
+
+        for vx in -2..=2 {
+            for vy in -2..=2 {
+                let velocity = Vector2::new(vx as f64, vy as f64) * 0.75;
+                let landed = landing(position, velocity, target, speed);
+
+                assert!(
+                    (landed - target).magnitude() < 0.01,
+                    "missed by {} at {:?}",
+                    (landed - target).magnitude(),
+                    velocity
+                );
+            }
+        }
+    }
+}
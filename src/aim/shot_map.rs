@@ -0,0 +1,193 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::shooter::{FLYWHEEL_DIAMETER, NOTE_EXIT_EFFICIENCY, SHOT_PITCH};
+
+/// flywheel setpoint for one distance
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Shot {
+    /// meters from the speaker
+    pub distance: f64,
+    /// top flywheel rpm
+    pub top: f64,
+    /// bottom flywheel rpm
+    pub bottom: f64,
+}
+
+impl Shot {
+    /// horizontal note speed leaving the shooter, meters per second
+    pub fn exit_speed(&self) -> f64 {
+        let rpm = (self.top + self.bottom) / 2.;
+        let surface = rpm / 60. * std::f64::consts::PI * FLYWHEEL_DIAMETER;
+
+        surface * NOTE_EXIT_EFFICIENCY * SHOT_PITCH.to_radians().cos()
+    }
+
+    fn lerp(&self, other: &Shot, distance: f64) -> Shot {
+        let t = (distance - self.distance) / (other.distance - self.distance);
+        Shot {
+            distance,
+            top: self.top + (other.top - self.top) * t,
+            bottom: self.bottom + (other.bottom - self.bottom) * t,
+        }
+    }
+}
+
+/// Flywheel speeds keyed on distance to the speaker, tuned on the field
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ShotMap {
+    shots: Vec<Shot>,
+}
+
+impl ShotMap {
+    const PATH: &'static str = "/home/lvuser/shot_map.json";
+
+    /// load from the rio, falling back to the built in table
+    pub fn load() -> Self {
+        Self::load_from(Path::new(Self::PATH))
+    }
+
+    fn load_from(path: &Path) -> Self {
+        let mut buf = String::new();
+        let loaded = File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut buf))
+            .ok()
+            .and_then(|_| serde_json::from_str::<Self>(&buf).ok());
+
+        match loaded {
+            Some(map) if !map.shots.is_empty() => map.sorted(),
+            _ => {
+                println!("shot map not found at {}, using defaults", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    fn sorted(mut self) -> Self {
+        self.shots.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        self
+    }
+
+    /// interpolated setpoint, clamped to the ends of the table
+    pub fn get(&self, distance: f64) -> Option<Shot> {
+        let first = self.shots.first()?;
+        let last = self.shots.last()?;
+
+        if distance <= first.distance {
+            return Some(Shot { distance, ..*first });
+        }
+        if distance >= last.distance {
+            return Some(Shot { distance, ..*last });
+        }
+
+        self.shots
+            .windows(2)
+            .find(|pair| distance <= pair[1].distance)
+            .map(|pair| pair[0].lerp(&pair[1], distance))
+    }
+}
+
+impl Default for ShotMap {
+    fn default() -> Self {
+        Self {
+            shots: vec![
+                // line shot
+                Shot {
+                    distance: 1.35,
+                    top: 5000.,
+                    bottom: 5000.,
+                },
+                // podium
+                Shot {
+                    distance: 2.95,
+                    top: 2080.,
+                    bottom: 1917.,
+                },
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{Shot, ShotMap};
+
+    fn shot(distance: f64, top: f64, bottom: f64) -> Shot {
+        Shot {
+            distance,
+            top,
+            bottom,
+        }
+    }
+
+    fn map(shots: Vec<Shot>) -> ShotMap {
+        ShotMap { shots }.sorted()
+    }
+
+    #[test]
+    fn interpolates_between_points() {
+        let map = map(vec![shot(1., 4000., 3000.), shot(3., 2000., 2000.)]);
+
+        assert_eq!(map.get(2.), Some(shot(2., 3000., 2500.)));
+        assert_eq!(map.get(1.), Some(shot(1., 4000., 3000.)));
+    }
+
+    #[test]
+    fn clamps_outside_the_table() {
+        let map = map(vec![shot(1., 4000., 3000.), shot(3., 2000., 2000.)]);
+
+        assert_eq!(map.get(0.5), Some(shot(0.5, 4000., 3000.)));
+        assert_eq!(map.get(10.), Some(shot(10., 2000., 2000.)));
+        assert_eq!(ShotMap { shots: vec![] }.get(2.), None);
+    }
+
+    #[test]
+    fn sorts_unsorted_points() {
+        let map = map(vec![
+            shot(3., 2000., 2000.),
+            shot(1., 4000., 3000.),
+            shot(2., 3500., 2500.),
+        ]);
+
+        assert_eq!(map.get(1.5), Some(shot(1.5, 3750., 2750.)));
+        assert_eq!(map.get(2.5), Some(shot(2.5, 2750., 2250.)));
+    }
+
+    #[test]
+    fn falls_back_on_a_bad_file() {
+        let directory = std::env::temp_dir().join("shot_map_test");
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        let defaults = ShotMap::default().shots;
+
+        let missing = ShotMap::load_from(&directory.join("missing.json"));
+        assert_eq!(missing.shots, defaults);
+
+        let garbled = directory.join("garbled.json");
+        fs::write(&garbled, "{ not json").unwrap();
+        assert_eq!(ShotMap::load_from(&garbled).shots, defaults);
+
+        let empty = directory.join("empty.json");
+        fs::write(&empty, r#"{ "shots": [] }"#).unwrap();
+        assert_eq!(ShotMap::load_from(&empty).shots, defaults);
+
+        let good = directory.join("good.json");
+        let unsorted = r#"{ "shots": [
+            { "distance": 3, "top": 2000, "bottom": 2000 },
+            { "distance": 1, "top": 4000, "bottom": 3000 }
+        ] }"#;
+        fs::write(&good, unsorted).unwrap();
+        let loaded = ShotMap::load_from(&good);
+        assert_eq!(
+            loaded.shots,
+            vec![shot(1., 4000., 3000.), shot(3., 2000., 2000.)]
+        );
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
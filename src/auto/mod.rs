@@ -7,7 +7,7 @@ use tokio::{
     fs::File,
     io::AsyncReadExt,
     join,
-    time::{sleep, timeout},
+    time::sleep,
 };
 use uom::si::{angle::degree, f64::Angle};
 use wpi_trajectory::Path;
@@ -17,19 +17,25 @@ use crate::{
         drivetrain::SWERVE_DRIVE_SUGGESTION_ERR,
         intake::{INTAKE_DOWN_GOAL, INTAKE_DOWN_THRESHOLD, INTAKE_UP_GOAL, INTAKE_UP_THRESHOLD},
     },
+    field::AllianceFlip,
     input::{lower_intake_trapezoidal, raise_intake_trapezoidal, stage, Ferris},
-    subsystems::{wait, Intake, Shooter},
-    telemetry::Picker,
+    subsystems::{wait, within, Intake, Outcome, Sequence, Shooter},
+    telemetry::{put_text, Picker, Pose, TelemetryStore},
 };
 
 use num_derive::{FromPrimitive, ToPrimitive};
 use num_traits::{FromPrimitive, ToPrimitive};
-use serde::de::IntoDeserializer;
+use serde::{de::IntoDeserializer, Serialize};
 use tokio::io::join;
 
 use self::path::{follow_path, follow_path_range};
+use self::start::{place, trajectory_path, Start};
+
+pub use self::readiness::publish_readiness;
 
 pub mod path;
+pub mod readiness;
+pub mod start;
 
 #[derive(Clone, FromPrimitive, ToPrimitive)]
 pub enum Auto {
@@ -90,20 +96,106 @@ impl Auto {
         }
     }
 
+    /// stable across reorderings and renames, what the dashboard picks by
+    /// and what gets stored
+    pub fn id(&self) -> &'static str {
+        match self {
+            Auto::TopStop => "top_stop",
+            Auto::BottomClose => "bottom_close",
+            Auto::TopWait => "top_wait",
+            Auto::TopBlock => "top_block",
+            Auto::BottomCloseWait => "bottom_close_wait",
+            Auto::BottomTwoLeave => "bottom_two_leave",
+            Auto::BottomWait => "bottom_wait",
+            Auto::ZeroIntake => "zero_intake",
+            Auto::StageOne => "stage_one",
+            Auto::BottomLeave => "bottom_leave",
+            Auto::BottomMidline => "bottom_midline",
+            Auto::TopMid2 => "top_mid_2",
+            Auto::BottomWaitMid => "bottom_wait_mid",
+            Auto::Nop => "nop",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all().into_iter().find(|auto| auto.id() == id)
+    }
+
+    /// the trajectory it starts with, None if it doesn't drive. `place` hands
+    /// it to the routine to drive first, so this is the only place it's named
+    pub fn first_trajectory(&self) -> Option<&'static str> {
+        match self {
+            Auto::TopStop => Some("TopStop.1"),
+            Auto::BottomClose => Some("BottomClose.1"),
+            Auto::TopWait => Some("TopOne.1"),
+            Auto::TopBlock => Some("TopOneBlock.1"),
+            Auto::BottomCloseWait => Some("BottomCloseWait.1"),
+            Auto::BottomTwoLeave => Some("BottomTwoLeave.1"),
+            Auto::BottomWait | Auto::StageOne => Some("BottomOne.1"),
+            Auto::BottomLeave => Some("BottomLeave.1"),
+            Auto::BottomMidline => Some("BottomMid.1"),
+            Auto::TopMid2 => Some("TopMid.1"),
+            Auto::BottomWaitMid => Some("BottomWaitMid.1"),
+            Auto::ZeroIntake | Auto::Nop => None,
+        }
+    }
+
+    /// blue side start, from the first sample of `first_trajectory`
+    pub fn start(&self) -> Option<Start> {
+        self.first_trajectory().and_then(Start::load)
+    }
+
+    /// zeroes the intake before it moves it, so it can start unhomed
+    pub fn homes_intake(&self) -> bool {
+        !matches!(self, Auto::Nop)
+    }
+
+    /// shoots the note it starts with
+    pub fn scores_preload(&self) -> bool {
+        !matches!(self, Auto::ZeroIntake | Auto::BottomLeave | Auto::Nop)
+    }
+
+    /// everything the drive team should check before the match
+    pub fn confirmation(&self, flip: &AllianceFlip) -> Confirmation {
+        Confirmation::new(self, self.start(), flip)
+    }
+
     pub fn len() -> usize {
         mem::variant_count::<Self>()
     }
 
-    pub fn names() -> Vec<String> {
+    pub fn all() -> Vec<Self> {
         (0..Self::len())
-            .map(|n| Self::from_usize(n).unwrap().name().to_owned())
+            .map(|n| Self::from_usize(n).unwrap())
             .collect()
     }
 
-    pub fn picker() -> Picker {
+    pub fn picker(&self) -> Picker {
+        let all = Self::all();
         Picker {
-            options: Auto::names(),
-            selected: Auto::default().to_usize().unwrap().to_string(),
+            options: all.iter().map(|auto| auto.id().to_owned()).collect(),
+            labels: all.iter().map(|auto| auto.name().to_owned()).collect(),
+            selected: self.id().to_owned(),
+        }
+    }
+
+    const PATH: &'static str = "/home/lvuser/auto.txt";
+
+    /// the last auto picked, so a reboot can't quietly change it
+    pub fn load() -> Self {
+        let loaded = std::fs::read_to_string(Self::PATH)
+            .ok()
+            .and_then(|id| Self::from_id(id.trim()));
+
+        loaded.unwrap_or_else(|| {
+            println!("no stored auto at {}, using the default", Self::PATH);
+            Self::default()
+        })
+    }
+
+    pub fn store(&self) {
+        if let Err(e) = std::fs::write(Self::PATH, self.id()) {
+            println!("couldn't store the auto selection: {e}");
         }
     }
 }
@@ -113,6 +205,37 @@ impl Default for Auto {
         Auto::ZeroIntake
     }
 }
+
+/// What's about to run, published while we wait for the match
+#[derive(Serialize, Clone, Debug)]
+pub struct Confirmation {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub alliance: &'static str,
+    /// where to place the robot, on our side of the field
+    pub start: Option<Pose>,
+    pub handling: &'static str,
+}
+
+impl Confirmation {
+    pub fn new(auto: &Auto, start: Option<Start>, flip: &AllianceFlip) -> Self {
+        let handling = match (auto.first_trajectory(), start, flip.flips()) {
+            (None, ..) => "doesn't drive",
+            (Some(_), None, _) => "couldn't read its first trajectory",
+            (Some(_), Some(_), false) => "paths run as drawn",
+            (Some(_), Some(_), true) => "paths mirrored for red",
+        };
+
+        Self {
+            id: auto.id(),
+            name: auto.name(),
+            alliance: if flip.red() { "red" } else { "blue" },
+            start: start.map(|start| start.pose(flip)),
+            handling,
+        }
+    }
+}
+
 pub struct AutoChooser(Chooser<Auto>);
 
 impl AutoChooser {
@@ -129,6 +252,22 @@ impl AutoChooser {
     }
 }
 
+/// whatever's picked on the dashboard, already checked by `set_key`
+pub async fn chosen_auto(robot: &Ferris) -> Auto {
+    robot.telemetry.read().await.auto.clone()
+}
+
+pub async fn publish_confirmation(telemetry: &TelemetryStore) {
+    let confirmation = telemetry
+        .read()
+        .await
+        .auto
+        .confirmation(&AllianceFlip::current());
+    if let Ok(confirmation) = serde_json::to_string(&confirmation) {
+        put_text("auto confirmation", &confirmation).await;
+    }
+}
+
 pub async fn run_auto(auto: Auto, robot: Ferris) {
     match auto {
         //Auto::Short => auto_short(robot).await,
@@ -177,7 +316,7 @@ async fn drive_err(
     acceptable_error: f64,
 ) {
     let mut path = String::new();
-    File::open(format!("/home/lvuser/deploy/choreo/{}.traj", name))
+    File::open(trajectory_path(name))
         .await
         .unwrap()
         .read_to_string(&mut path)
@@ -191,7 +330,7 @@ async fn drive_err(
 
 async fn drive(name: &str, drivetrain: &mut crate::subsystems::Drivetrain) {
     let mut path = String::new();
-    File::open(format!("/home/lvuser/deploy/choreo/{}.traj", name))
+    File::open(trajectory_path(name))
         .await
         .unwrap()
         .read_to_string(&mut path)
@@ -218,9 +357,9 @@ async fn bottom_wait_mid(robot: Ferris) {
     let mut drivetrain = robot.drivetrain.deref().borrow_mut();
     let mut shooter = robot.shooter.deref().borrow_mut();
 
-    drivetrain.odometry.set(Vector2::new(0.469, 2.0862367153167725));
-    drivetrain.reset_angle();
-    drivetrain.reset_heading();
+    let Some(first) = place(&Auto::BottomWaitMid, &mut drivetrain, &robot.telemetry).await else {
+        return;
+    };
 
     join!(
         intake.zero(),
@@ -229,9 +368,9 @@ async fn bottom_wait_mid(robot: Ferris) {
 
     shooter.set_shooter(5500.);
 
-    drive("BottomWaitMid.1", &mut drivetrain).await;
+    drive(first, &mut drivetrain).await;
 
-    sushi_shoot(&mut shooter).await;
+    report("shoot", sushi_shoot(&mut shooter).await);
 
     let mut failure = false;
 
@@ -240,18 +379,19 @@ async fn bottom_wait_mid(robot: Ferris) {
         async {
             sleep(Duration::from_secs_f64(3.)).await;
             intake.set_rollers(0.4);
-            failure = timeout(Duration::from_millis(2500), intake.grab())
+            failure = within(Duration::from_millis(2500), intake.grab())
                 .await
                 .is_err();
         }
     );
 
-    join!(
+    let (_, staged) = join!(
         drive("BottomWaitMid.2", &mut drivetrain),
-        timeout(Duration::from_millis(3000), stage(&mut intake, &shooter))
+        within(Duration::from_millis(3000), stage(&mut intake, &shooter))
     );
+    report("stage", staged);
 
-    shoot(&mut intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
 
     shooter.set_shooter(0.)
 }
@@ -261,18 +401,19 @@ async fn top_mid_two(robot: Ferris) {
     let mut drivetrain = robot.drivetrain.deref().borrow_mut();
     let mut shooter = robot.shooter.deref().borrow_mut();
 
-    drivetrain.odometry.set(Vector2::new(0.469, 7.034497));
-    drivetrain.reset_angle();
-    drivetrain.reset_heading();
+    let Some(first) = place(&Auto::TopMid2, &mut drivetrain, &robot.telemetry).await else {
+        return;
+    };
 
     shooter.set_velocity(5500.);
 
     join!(
-        drive("TopMid.1", &mut drivetrain),
+        drive(first, &mut drivetrain),
         intake.zero(),
     );
 
-    join!(sushi_shoot(&mut shooter), lower_intake(&mut intake));
+    let (shot, _) = join!(sushi_shoot(&mut shooter), lower_intake(&mut intake));
+    report("shoot", shot);
 
     intake.set_rollers(0.4);
 
@@ -280,7 +421,7 @@ async fn top_mid_two(robot: Ferris) {
     join!(
         drive("TopMid.2", &mut drivetrain),
         async {
-            failure = timeout(Duration::from_millis(2000), intake.grab())
+            failure = within(Duration::from_millis(2000), intake.grab())
                 .await
                 .is_err();
         }
@@ -290,13 +431,14 @@ async fn top_mid_two(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(1300), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(1300), stage(&mut intake, &shooter)),
         drive("TopMid.3", &mut drivetrain)
     );
+    report("stage", staged);
 
     raise_intake(&mut intake).await;
-    let _ = timeout(Duration::from_millis(1000), shoot(&intake, &mut shooter)).await;
+    report("shoot", within(Duration::from_millis(1000), shoot(&intake, &mut shooter)).await);
 
     drive("TopMid.4", &mut drivetrain).await;
 
@@ -305,7 +447,7 @@ async fn top_mid_two(robot: Ferris) {
         async {
             sleep(Duration::from_secs_f64(1.5)).await;
             lower_intake(&mut intake).await;
-            failure = timeout(Duration::from_millis(2000), intake.grab())
+            failure = within(Duration::from_millis(2000), intake.grab())
                 .await
                 .is_err();
         }
@@ -315,14 +457,15 @@ async fn top_mid_two(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(2000), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(2000), stage(&mut intake, &shooter)),
         drive("TopMid.6", &mut drivetrain)
     );
+    report("stage", staged);
 
     drive("TopMid.7", &mut drivetrain).await;
 
-    shoot(&mut intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
 
     shooter.set_shooter(0.);
 }
@@ -332,20 +475,18 @@ async fn bottom_mid(robot: Ferris) {
     let mut intake = robot.intake.deref().borrow_mut();
     let mut shooter = robot.shooter.deref().borrow_mut();
 
-    drivetrain
-        .odometry
-        .set(Vector2::new(0.4694126546382904, 4.043473720550537));
-    drivetrain.reset_angle();
-    drivetrain.reset_heading();
+    let Some(first) = place(&Auto::BottomMidline, &mut drivetrain, &robot.telemetry).await else {
+        return;
+    };
 
     shooter.set_velocity(5500.);
 
     join!(
-        drive("BottomMid.1", &mut drivetrain),
+        drive(first, &mut drivetrain),
         intake.zero()
     );
 
-    sushi_shoot(&mut shooter).await;
+    report("shoot", sushi_shoot(&mut shooter).await);
 
     drive("BottomMid.2", &mut drivetrain).await;
 
@@ -357,7 +498,7 @@ async fn bottom_mid(robot: Ferris) {
             lower_intake(&mut intake).await;
             intake.set_rollers(0.4);
 
-            failure = timeout(Duration::from_millis(2500), intake.grab())
+            failure = within(Duration::from_millis(2500), intake.grab())
                 .await
                 .is_err();
         }
@@ -367,14 +508,15 @@ async fn bottom_mid(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    join!(
-        timeout(Duration::from_millis(2500), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(2500), stage(&mut intake, &shooter)),
         drive("BottomMid.4", &mut drivetrain)
     );
+    report("stage", staged);
 
     drive("BottomMid.4", &mut drivetrain).await;
 
-    shoot(&mut intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
 
     shooter.set_shooter(0.);
     shooter.set_feeder(0.);
@@ -386,14 +528,12 @@ async fn bottom_leave(robot: Ferris) {
     let mut intake = robot.intake.deref().borrow_mut();
     let mut shooter = robot.shooter.deref().borrow_mut();
 
-    drivetrain
-        .odometry
-        .set(Vector2::new(0.4694126546382904, 2.074124813079834));
-    drivetrain.reset_angle();
-    drivetrain.reset_heading();
+    let Some(first) = place(&Auto::BottomLeave, &mut drivetrain, &robot.telemetry).await else {
+        return;
+    };
 
     join!(
-        drive("BottomLeave.1", &mut drivetrain),
+        drive(first, &mut drivetrain),
         intake.zero()
     );
 
@@ -409,25 +549,24 @@ async fn stage_one(robot: Ferris) {
     let mut shooter = robot.shooter.deref().borrow_mut();
     let _telemetry = robot.telemetry.clone();
 
-    drivetrain
-        .odometry
-        .set(Vector2::new(0.4808354377746582, 4.043473720550537));
-    drivetrain.reset_angle();
-    drivetrain.reset_heading();
+    let Some(first) = place(&Auto::StageOne, &mut drivetrain, &robot.telemetry).await else {
+        return;
+    };
 
     sleep(Duration::from_secs_f64(10.)).await;
 
     shooter.set_shooter(1.0);
 
     join!(
-        drive("BottomOne.1", &mut drivetrain), // scoring position
+        drive(first, &mut drivetrain), // scoring position
         intake.zero(),
     );
 
-    join!(
+    let (shot, _) = join!(
         sushi_shoot(&mut shooter),
         lower_intake(&mut intake)
     );
+    report("shoot", shot);
 
     shooter.set_shooter(0.);
     drive("BottomOne.2", &mut drivetrain).await;
@@ -438,18 +577,19 @@ async fn top_stop(robot: Ferris) {
     let mut drivetrain = robot.drivetrain.deref().borrow_mut();
     let mut shooter = robot.shooter.deref().borrow_mut();
 
-    drivetrain.odometry.set(Vector2::new(0.469, 7.034497));
-    drivetrain.reset_angle();
-    drivetrain.reset_heading();
+    let Some(first) = place(&Auto::TopStop, &mut drivetrain, &robot.telemetry).await else {
+        return;
+    };
 
     shooter.set_velocity(5500.);
 
     join!(
-        drive("TopStop.1", &mut drivetrain), // scoring position
+        drive(first, &mut drivetrain), // scoring position
         intake.zero(),
     );
 
-    join!(sushi_shoot(&mut shooter), lower_intake(&mut intake));
+    let (shot, _) = join!(sushi_shoot(&mut shooter), lower_intake(&mut intake));
+    report("shoot", shot);
 
     intake.set_rollers(0.4);
 
@@ -457,7 +597,7 @@ async fn top_stop(robot: Ferris) {
     join!(
         drive("TopStop.2", &mut drivetrain), // goto note
         async {
-            failure = timeout(Duration::from_millis(2000), intake.grab())
+            failure = within(Duration::from_millis(2000), intake.grab())
                 .await
                 .is_err();
         }
@@ -467,20 +607,21 @@ async fn top_stop(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(1300), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(1300), stage(&mut intake, &shooter)),
         drive("TopStop.3", &mut drivetrain) // scoring position
     );
+    report("stage", staged);
 
     raise_intake(&mut intake).await;
-    let _ = timeout(Duration::from_millis(1000), shoot(&intake, &mut shooter)).await;
+    report("shoot", within(Duration::from_millis(1000), shoot(&intake, &mut shooter)).await);
     lower_intake(&mut intake).await;
 
     let mut failure = false;
     join!(
         drive("TopStop.4", &mut drivetrain), // next note
         async {
-            failure = timeout(Duration::from_millis(2000), intake.grab())
+            failure = within(Duration::from_millis(2000), intake.grab())
                 .await
                 .is_err();
         },
@@ -490,13 +631,14 @@ async fn top_stop(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(1300), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(1300), stage(&mut intake, &shooter)),
         drive("TopStop.5", &mut drivetrain) // scoring position
     );
+    report("stage", staged);
 
     raise_intake(&mut intake).await;
-    let _ = timeout(Duration::from_millis(1000), shoot(&intake, &mut shooter)).await;
+    report("shoot", within(Duration::from_millis(1000), shoot(&intake, &mut shooter)).await);
     lower_intake(&mut intake).await;
 
     let mut failure = false;
@@ -504,7 +646,7 @@ async fn top_stop(robot: Ferris) {
     join!(
         drive_err("TopStop.6", &mut drivetrain, SWERVE_DRIVE_SUGGESTION_ERR),
         async {
-            failure = timeout(Duration::from_millis(2500), intake.grab())
+            failure = within(Duration::from_millis(2500), intake.grab())
                 .await
                 .is_err();
         }
@@ -514,13 +656,14 @@ async fn top_stop(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(1300), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(1300), stage(&mut intake, &shooter)),
         drive("TopStop.7", &mut drivetrain) // scoring position
     );
+    report("stage", staged);
 
     raise_intake(&mut intake).await;
-    shoot(&intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
 
     shooter.set_shooter(0.);
 }
@@ -558,7 +701,7 @@ async fn top(robot: Ferris) {
     join!(
         drive("Top.2", &mut drivetrain), // goto note
         async {
-            failure = timeout(Duration::from_millis(3000), intake.grab())
+            failure = within(Duration::from_millis(3000), intake.grab())
                 .await
                 .is_err();
         }
@@ -568,19 +711,20 @@ async fn top(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(2500), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(2500), stage(&mut intake, &shooter)),
         drive("Top.3", &mut drivetrain) // scoring position
     );
+    report("stage", staged);
 
-    shoot(&intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
 
     let mut failure = false;
     join!(
         drive("Top.4", &mut drivetrain), // next note
         async {
             lower_intake(&mut intake).await;
-            failure = timeout(Duration::from_millis(3000), intake.grab())
+            failure = within(Duration::from_millis(3000), intake.grab())
                 .await
                 .is_err();
         },
@@ -590,12 +734,13 @@ async fn top(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(2500), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(2500), stage(&mut intake, &shooter)),
         drive("Top.5", &mut drivetrain) // scoring position
     );
+    report("stage", staged);
 
-    shoot(&intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
 
     let mut failure = false;
 
@@ -603,7 +748,7 @@ async fn top(robot: Ferris) {
         drive("Top.6", &mut drivetrain), // goto note
         async {
             lower_intake(&mut intake).await;
-            failure = timeout(Duration::from_millis(3000), intake.grab())
+            failure = within(Duration::from_millis(3000), intake.grab())
                 .await
                 .is_err();
         }
@@ -613,12 +758,13 @@ async fn top(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(2500), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(2500), stage(&mut intake, &shooter)),
         drive("Top.7", &mut drivetrain) // scoring position
     );
+    report("stage", staged);
 
-    shoot(&intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
 
     shooter.set_shooter(0.);
 }
@@ -655,14 +801,13 @@ async fn source_out(robot: Ferris) {
     );
     intake.set_rollers(1.);
 
-    let _ = join!(
+    let (_, staged) = join!(
         drive("SourceTwo.4", &mut drivetrain), // scoring position
-        async {
-            let _ = timeout(Duration::from_millis(2500), stage(&mut intake, &shooter)).await;
-        }
+        within(Duration::from_millis(2500), stage(&mut intake, &shooter))
     );
+    report("stage", staged);
 
-    shoot(&intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
 
     shooter.set_shooter(0.);
 }
@@ -702,7 +847,7 @@ async fn Triple_Note(robot: Ferris) {
     join!(
         drive("3Note.2", &mut drivetrain), // goto note
         async {
-            failure = timeout(Duration::from_millis(3000), intake.grab())
+            failure = within(Duration::from_millis(3000), intake.grab())
                 .await
                 .is_err();
         }
@@ -712,19 +857,20 @@ async fn Triple_Note(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(2500), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(2500), stage(&mut intake, &shooter)),
         drive("3Note.3", &mut drivetrain) // scoring position
     );
+    report("stage", staged);
 
-    shoot(&intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
 
     let mut failure = false;
     join!(
         drive("3Note.4", &mut drivetrain), // next note
         async {
             lower_intake(&mut intake).await;
-            failure = timeout(Duration::from_millis(3000), intake.grab())
+            failure = within(Duration::from_millis(3000), intake.grab())
                 .await
                 .is_err();
         },
@@ -734,19 +880,20 @@ async fn Triple_Note(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(2500), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(2500), stage(&mut intake, &shooter)),
         drive("3Note.5", &mut drivetrain) // scoring position
     );
+    report("stage", staged);
 
-    shoot(&intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
 
     let mut failure = false;
     join!(
         drive("3Note.6", &mut drivetrain), // goto note
         async {
             lower_intake(&mut intake).await;
-            failure = timeout(Duration::from_millis(3000), intake.grab())
+            failure = within(Duration::from_millis(3000), intake.grab())
                 .await
                 .is_err();
         }
@@ -756,12 +903,13 @@ async fn Triple_Note(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(2500), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(2500), stage(&mut intake, &shooter)),
         drive("3Note.7", &mut drivetrain) // scoring position
     );
+    report("stage", staged);
 
-    shoot(&intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
 
     shooter.set_shooter(0.);
 }
@@ -775,9 +923,7 @@ async fn auto_short(robot: Ferris) {
     shooter.set_shooter(1.0);
     intake.set_rollers(-0.1);
 
-    if let Err(_) = timeout(Duration::from_secs_f64(1.4), shooter.load()).await {
-        shooter.stop_feeder();
-    };
+    report("load", within(Duration::from_secs_f64(1.4), shooter.load()).await);
     wait(|| shooter.get_velocity() > 5000.).await;
     intake.set_rollers(0.0);
 
@@ -824,7 +970,7 @@ async fn center(robot: Ferris) {
     join!(
         drive("Top.2", &mut drivetrain), // goto note
         async {
-            failure = timeout(Duration::from_millis(1000), intake.grab())
+            failure = within(Duration::from_millis(1000), intake.grab())
                 .await
                 .is_err();
         }
@@ -834,12 +980,13 @@ async fn center(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(2500), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(2500), stage(&mut intake, &shooter)),
         drive("Top.3", &mut drivetrain) // scoring position
     );
+    report("stage", staged);
 
-    shoot(&intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
     lower_intake(&mut intake).await;
     intake.set_rollers(0.4);
 
@@ -847,7 +994,7 @@ async fn center(robot: Ferris) {
     join!(
         drive("Top.4", &mut drivetrain), // next note
         async {
-            failure = timeout(Duration::from_millis(1000), intake.grab())
+            failure = within(Duration::from_millis(1000), intake.grab())
                 .await
                 .is_err();
         }
@@ -857,12 +1004,13 @@ async fn center(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(2500), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(2500), stage(&mut intake, &shooter)),
         drive("Top.5", &mut drivetrain) // scoring position
     );
+    report("stage", staged);
 
-    shoot(&intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
     shooter.set_shooter(0.);
 }
 
@@ -894,7 +1042,7 @@ async fn bottom(robot: Ferris) {
         async {
             sleep(Duration::from_millis(1750)).await;
             lower_intake(&mut intake).await;
-            failure = timeout(Duration::from_millis(3000), intake.grab())
+            failure = within(Duration::from_millis(3000), intake.grab())
                 .await
                 .is_err();
         }
@@ -904,17 +1052,21 @@ async fn bottom(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(2500), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(2500), stage(&mut intake, &shooter)),
         drive("Bottom.3", &mut drivetrain) // scoring position
     );
+    report("stage", staged);
 
-    shoot(&intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
     shooter.set_shooter(0.);
 }
 
 pub async fn lower_intake(intake: &mut Intake) {
     lower_intake_trapezoidal(intake).await;
+    if !intake.homed() {
+        return;
+    }
 
     intake.actuate_to(Angle::new::<degree>(INTAKE_DOWN_GOAL));
     wait(|| intake.actuate_position().get::<degree>() < INTAKE_DOWN_THRESHOLD).await;
@@ -922,6 +1074,9 @@ pub async fn lower_intake(intake: &mut Intake) {
 
 pub async fn raise_intake(intake: &mut Intake) {
     raise_intake_trapezoidal(intake).await;
+    if !intake.homed() {
+        return;
+    }
 
     intake.actuate_to(Angle::new::<degree>(INTAKE_UP_GOAL));
     wait(|| intake.actuate_position().get::<degree>() > INTAKE_UP_THRESHOLD).await;
@@ -955,7 +1110,7 @@ async fn bottom_out(robot: Ferris) {
         async {
             sleep(Duration::from_millis(1750)).await;
             lower_intake(&mut intake).await;
-            failure = timeout(Duration::from_millis(3000), intake.grab())
+            failure = within(Duration::from_millis(3000), intake.grab())
                 .await
                 .is_err();
         }
@@ -965,12 +1120,13 @@ async fn bottom_out(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(2500), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(2500), stage(&mut intake, &shooter)),
         drive("BottomOut.3", &mut drivetrain) // scoring position
     );
+    report("stage", staged);
 
-    shoot(&intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
     shooter.set_shooter(0.);
 }
 
@@ -980,22 +1136,21 @@ async fn bottom_close(robot: Ferris) {
     let mut shooter = robot.shooter.deref().borrow_mut();
     let _telemetry = robot.telemetry.clone();
 
-    drivetrain
-        .odometry
-        .set(Vector2::new(0.4808354377746582, 4.043473720550537));
-    drivetrain.reset_angle();
-    drivetrain.reset_heading();
+    let Some(first) = place(&Auto::BottomClose, &mut drivetrain, &robot.telemetry).await else {
+        return;
+    };
 
     //shooter.set_shooter(1.0);
 
     shooter.set_velocity(5500.);
 
     join!(
-        drive("BottomClose.1", &mut drivetrain), // scoring position
+        drive(first, &mut drivetrain), // scoring position
         intake.zero(),
     );
 
-    join!(sushi_shoot(&mut shooter), lower_intake(&mut intake));
+    let (shot, _) = join!(sushi_shoot(&mut shooter), lower_intake(&mut intake));
+    report("shoot", shot);
 
     intake.set_rollers(0.4);
 
@@ -1003,7 +1158,7 @@ async fn bottom_close(robot: Ferris) {
     join!(
         drive("BottomClose.2", &mut drivetrain), // goto note
         async {
-            failure = timeout(Duration::from_millis(2500), intake.grab())
+            failure = within(Duration::from_millis(2500), intake.grab())
                 .await
                 .is_err();
         }
@@ -1013,13 +1168,14 @@ async fn bottom_close(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(1300), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(1300), stage(&mut intake, &shooter)),
         drive("BottomClose.3", &mut drivetrain) // scoring position
     );
+    report("stage", staged);
 
     raise_intake(&mut intake).await;
-    shoot(&intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
 
     lower_intake(&mut intake).await;
 
@@ -1027,7 +1183,7 @@ async fn bottom_close(robot: Ferris) {
     join!(
         drive("BottomClose.4", &mut drivetrain), // next note
         async {
-            failure = timeout(Duration::from_millis(2000), intake.grab())
+            failure = within(Duration::from_millis(2000), intake.grab())
                 .await
                 .is_err();
         },
@@ -1037,13 +1193,14 @@ async fn bottom_close(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(500), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(500), stage(&mut intake, &shooter)),
         drive("BottomClose.5", &mut drivetrain) // scoring position
     );
+    report("stage", staged);
 
     raise_intake(&mut intake).await;
-    shoot(&intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
 
     lower_intake(&mut intake).await;
 
@@ -1054,7 +1211,7 @@ async fn bottom_close(robot: Ferris) {
     join!(
         drive("BottomClose.6", &mut drivetrain), // goto note
         async {
-            failure = timeout(Duration::from_millis(2500), intake.grab())
+            failure = within(Duration::from_millis(2500), intake.grab())
                 .await
                 .is_err();
         }
@@ -1064,10 +1221,11 @@ async fn bottom_close(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(1300), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(1300), stage(&mut intake, &shooter)),
         drive("BottomClose.7", &mut drivetrain) // scoring position
     );
+    report("stage", staged);
 
     raise_intake(&mut intake).await;
     wait(|| shooter.get_velocity() > 5400.).await;
@@ -1089,17 +1247,15 @@ async fn bottom_one(robot: Ferris) {
     let mut shooter = robot.shooter.deref().borrow_mut();
     let _telemetry = robot.telemetry.clone();
 
-    drivetrain
-        .odometry
-        .set(Vector2::new(0.4808354377746582, 4.043473720550537));
-    drivetrain.reset_angle();
-    drivetrain.reset_heading();
+    let Some(first) = place(&Auto::BottomWait, &mut drivetrain, &robot.telemetry).await else {
+        return;
+    };
 
     sleep(Duration::from_millis(7000)).await;
 
     shooter.set_shooter(1.0);
     join!(
-        drive("BottomOne.1", &mut drivetrain), // scoring position
+        drive(first, &mut drivetrain), // scoring position
         intake.zero(),
     );
 
@@ -1122,18 +1278,16 @@ async fn top_one(robot: Ferris) {
     let mut drivetrain = robot.drivetrain.deref().borrow_mut();
     let mut shooter = robot.shooter.deref().borrow_mut();
 
-    drivetrain
-        .odometry
-        .set(Vector2::new(0.46920153498649597, 7.0344977378845215));
-    drivetrain.reset_angle();
-    drivetrain.reset_heading();
+    let Some(first) = place(&Auto::TopWait, &mut drivetrain, &robot.telemetry).await else {
+        return;
+    };
 
     shooter.set_shooter(1.0);
     join!(intake.zero(), sleep(Duration::from_millis(10_000)),);
 
-    drive("TopOne.1", &mut drivetrain).await; // scoring position
+    drive(first, &mut drivetrain).await; // scoring position
 
-    sushi_shoot(&mut shooter).await;
+    report("shoot", sushi_shoot(&mut shooter).await);
 
     shooter.set_shooter(0.);
     drive("TopOne.2", &mut drivetrain).await;
@@ -1144,16 +1298,14 @@ async fn top_one_block(robot: Ferris) {
     let mut drivetrain = robot.drivetrain.deref().borrow_mut();
     let mut shooter = robot.shooter.deref().borrow_mut();
 
-    drivetrain
-        .odometry
-        .set(Vector2::new(0.46920153498649597, 7.0344977378845215));
-    drivetrain.reset_angle();
-    drivetrain.reset_heading();
+    let Some(first) = place(&Auto::TopBlock, &mut drivetrain, &robot.telemetry).await else {
+        return;
+    };
 
     shooter.set_shooter(1.0);
     join!(intake.zero(), sleep(Duration::from_millis(6000)),);
 
-    drive("TopOneBlock.1", &mut drivetrain).await; // scoring position
+    drive(first, &mut drivetrain).await; // scoring position
 
     // shoot
     wait(|| shooter.get_velocity() > 5000.).await;
@@ -1170,20 +1322,19 @@ async fn bottom_two_leave(robot: Ferris) {
     let mut drivetrain = robot.drivetrain.deref().borrow_mut();
     let mut shooter = robot.shooter.deref().borrow_mut();
 
-    drivetrain
-        .odometry
-        .set(Vector2::new(0.4808354377746582, 4.043473720550537));
-    drivetrain.reset_angle();
-    drivetrain.reset_heading();
+    let Some(first) = place(&Auto::BottomTwoLeave, &mut drivetrain, &robot.telemetry).await else {
+        return;
+    };
 
     shooter.set_velocity(5500.);
 
     join!(
-        drive("BottomTwoLeave.1", &mut drivetrain), // scoring position
+        drive(first, &mut drivetrain), // scoring position
         intake.zero(),
     );
 
-    join!(sushi_shoot(&mut shooter), lower_intake(&mut intake));
+    let (shot, _) = join!(sushi_shoot(&mut shooter), lower_intake(&mut intake));
+    report("shoot", shot);
 
     intake.set_rollers(0.4);
 
@@ -1191,7 +1342,7 @@ async fn bottom_two_leave(robot: Ferris) {
     join!(
         drive("BottomTwoLeave.2", &mut drivetrain), // goto note
         async {
-            failure = timeout(Duration::from_millis(3000), intake.grab())
+            failure = within(Duration::from_millis(3000), intake.grab())
                 .await
                 .is_err();
         }
@@ -1201,13 +1352,14 @@ async fn bottom_two_leave(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(2500), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(2500), stage(&mut intake, &shooter)),
         drive("BottomTwoLeave.3", &mut drivetrain) // scoring position
     );
+    report("stage", staged);
 
     raise_intake(&mut intake).await;
-    shoot(&intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
 
     shooter.set_shooter(0.);
 
@@ -1219,17 +1371,15 @@ async fn bottom_close_wait(robot: Ferris) {
     let mut drivetrain = robot.drivetrain.deref().borrow_mut();
     let mut shooter = robot.shooter.deref().borrow_mut();
 
-    drivetrain
-        .odometry
-        .set(Vector2::new(0.4808354377746582, 4.043473720550537));
-    drivetrain.reset_angle();
-    drivetrain.reset_heading();
+    let Some(first) = place(&Auto::BottomCloseWait, &mut drivetrain, &robot.telemetry).await else {
+        return;
+    };
 
     shooter.set_shooter(1.0);
 
     sleep(Duration::from_millis(4000)).await;
     join!(
-        drive("BottomCloseWait.1", &mut drivetrain), // scoring position
+        drive(first, &mut drivetrain), // scoring position
         intake.zero(),
     );
 
@@ -1250,7 +1400,7 @@ async fn bottom_close_wait(robot: Ferris) {
     join!(
         drive("BottomCloseWait.2", &mut drivetrain), // goto note
         async {
-            failure = timeout(Duration::from_millis(3000), intake.grab())
+            failure = within(Duration::from_millis(3000), intake.grab())
                 .await
                 .is_err();
         }
@@ -1260,12 +1410,13 @@ async fn bottom_close_wait(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(2500), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(2500), stage(&mut intake, &shooter)),
         drive("BottomCloseWait.3", &mut drivetrain) // scoring position
     );
+    report("stage", staged);
 
-    shoot(&intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
 
     shooter.set_shooter(0.);
 }
@@ -1306,7 +1457,7 @@ async fn stage_close_far(robot: Ferris) {
     join!(
         drive("StageCloseFar.3", &mut drivetrain), // drive to note
         async {
-            failure = timeout(Duration::from_millis(2500), intake.grab())
+            failure = within(Duration::from_millis(2500), intake.grab())
                 .await
                 .is_err();
         }
@@ -1316,12 +1467,13 @@ async fn stage_close_far(robot: Ferris) {
         println!("womp womp :(");
     }
 
-    let _ = join!(
-        timeout(Duration::from_millis(5000), stage(&mut intake, &shooter)),
+    let (staged, _) = join!(
+        within(Duration::from_millis(5000), stage(&mut intake, &shooter)),
         drive("StageCloseFar.4", &mut drivetrain) // Shooting second
     );
+    report("stage", staged);
 
-    shoot(&intake, &mut shooter).await;
+    report("shoot", shoot(&intake, &mut shooter).await);
 
     shooter.set_shooter(0.);
 
@@ -1343,18 +1495,92 @@ async fn odo_test(robot: Ferris) {
     drive("OdoTest.2", &mut drivetrain).await;
 }
 
-async fn shoot(intake: &Intake, shooter: &mut Shooter) {
+/// say how a step went if it didn't finish, the routine carries on either way
+fn report(step: &str, outcome: Outcome) {
+    if let Err(interrupted) = outcome {
+        println!("auto {step} {interrupted}");
+    }
+}
+
+/// Feed the note through once the flywheel is up to speed, until the shot
+/// slows it
+///
+/// The feeder and rollers stop however it ends, so a `within` around it
+/// doesn't leave them running into the next drive
+async fn shoot(intake: &Intake, shooter: &mut Shooter) -> Outcome {
+    let mut sequence = Sequence::new("shoot", (intake, shooter), |(intake, shooter)| {
+        intake.stop_rollers();
+        shooter.stop_feeder();
+    });
+    let (intake, shooter) = &mut *sequence;
+
     wait(|| shooter.get_velocity() > 5400.).await;
     intake.set_rollers(-1.);
     shooter.set_feeder(-1.);
     wait(|| shooter.get_velocity() < 5300.).await;
-    shooter.set_feeder(0.);
-    intake.set_rollers(0.);
+
+    sequence.finish(Ok(()))
 }
 
-async fn sushi_shoot(shooter: &mut Shooter) {
+/// `shoot` for a note already in the shooter, with nothing from the intake
+async fn sushi_shoot(shooter: &mut Shooter) -> Outcome {
+    let mut sequence = Sequence::new("shoot", shooter, |shooter| shooter.stop_feeder());
+    let shooter = &mut *sequence;
+
     wait(|| shooter.get_velocity() > 5400.).await;
     shooter.set_feeder(-1.);
     wait(|| shooter.get_velocity() < 5300.).await;
-    shooter.set_feeder(0.);
+
+    sequence.finish(Ok(()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use nalgebra::Vector2;
+    use uom::si::{angle::degree, f64::Angle};
+
+    use super::{start::Start, Auto, Confirmation};
+    use crate::{constants::RED_REFLECTION_METERS, field::AllianceFlip};
+
+    #[test]
+    fn ids_round_trip() {
+        let ids: HashSet<_> = Auto::all().iter().map(|auto| auto.id()).collect();
+        assert_eq!(ids.len(), Auto::len());
+
+        for auto in Auto::all() {
+            assert_eq!(Auto::from_id(auto.id()).unwrap().id(), auto.id());
+        }
+        assert!(Auto::from_id("7").is_none());
+    }
+
+    #[test]
+    fn confirms_mirrored_start() {
+        let start = Some(Start {
+            position: Vector2::new(0.469, 7.034497),
+            heading: Angle::new::<degree>(20.),
+        });
+        let (blue, red) = (
+            AllianceFlip::for_alliance(false),
+            AllianceFlip::for_alliance(true),
+        );
+
+        let drawn = Confirmation::new(&Auto::TopStop, start, &blue);
+        let mirrored = Confirmation::new(&Auto::TopStop, start, &red);
+        assert_eq!(drawn.alliance, "blue");
+        assert_eq!(mirrored.alliance, "red");
+
+        let (drawn, mirrored) = (drawn.start.unwrap(), mirrored.start.unwrap());
+        assert_eq!(drawn.x, mirrored.x);
+        assert_eq!(mirrored.y, RED_REFLECTION_METERS - drawn.y);
+        assert_eq!(mirrored.theta, -drawn.theta);
+
+        let unread = Confirmation::new(&Auto::TopStop, None, &red);
+        assert!(unread.start.is_none());
+        assert_eq!(unread.handling, "couldn't read its first trajectory");
+
+        let stationary = Confirmation::new(&Auto::ZeroIntake, None, &red);
+        assert_eq!(stationary.handling, "doesn't drive");
+    }
 }
@@ -1,15 +1,8 @@
 use std::time::Duration;
 
-use frcrs::alliance_station;
 use nalgebra::Vector2;
 use tokio::time::{sleep, Instant};
-use uom::si::{
-    angle::radian,
-    f64::{Length, Time},
-    length::{foot, meter},
-    time::{millisecond, second},
-    velocity::meter_per_second,
-};
+use uom::si::{angle::radian, f64::Time, length::meter, time::second, velocity::meter_per_second};
 use wpi_trajectory::Path;
 
 use crate::{
@@ -17,15 +10,29 @@ use crate::{
         SWERVE_DRIVE_IE, SWERVE_DRIVE_KD, SWERVE_DRIVE_KF, SWERVE_DRIVE_KFA, SWERVE_DRIVE_KI,
         SWERVE_DRIVE_KP, SWERVE_DRIVE_MAX_ERR, SWERVE_TURN_KP,
     },
+    field::{AllianceFlip, Sample},
     subsystems::Drivetrain,
+    telemetry::TELEMETRY,
 };
 
+/// a trajectory sample as drawn, for `AllianceFlip` to turn into ours
+pub fn sample(path: &Path, time: Time) -> Sample {
+    let sample = path.get(time);
+
+    Sample {
+        position: Vector2::new(sample.x.get::<meter>(), sample.y.get::<meter>()),
+        velocity: Vector2::new(sample.velocity_x, sample.velocity_y)
+            .map(|v| v.get::<meter_per_second>()),
+        heading: sample.heading,
+    }
+}
+
 pub async fn follow_path(drivetrain: &mut Drivetrain, path: Path) {
     follow_path_range(drivetrain, path, SWERVE_DRIVE_MAX_ERR).await
 }
 pub async fn follow_path_range(drivetrain: &mut Drivetrain, path: Path, max_err: f64) {
     let start = Instant::now();
-    let red = alliance_station().red();
+    let flip = AllianceFlip::current();
 
     let mut last_error = Vector2::zeros(); // TODO: delta t
     let mut last_loop = Instant::now();
@@ -38,23 +45,15 @@ pub async fn follow_path_range(drivetrain: &mut Drivetrain, path: Path, max_err:
 
         let elapsed = Time::new::<second>(start.elapsed().as_secs_f64());
 
-        let mut setpoint = path.get(elapsed);
-        let mut setpoint_next = path.get(elapsed + Time::new::<millisecond>(20.)); // bodge
-
-        // TODO: red-blu detection
-        if red {
-            for setpoint in [&mut setpoint, &mut setpoint_next] {
-                setpoint.y = Length::new::<foot>(54. / 4.) - setpoint.y;
-                setpoint.velocity_y = -setpoint.velocity_y;
-                //setpoint.heading = Angle::new::<degree>(180.)- setpoint.heading;
-                setpoint.heading = -setpoint.heading;
-            }
-        }
+        let setpoint = flip.sample(sample(&path, elapsed));
 
-        let position = Vector2::new(setpoint.x.get::<meter>(), setpoint.y.get::<meter>());
+        let position = setpoint.position;
         let angle = -setpoint.heading;
 
-        //drivetrain.odometry.update_from_vision(telemetry.clone(), red).await;
+        drivetrain
+            .odometry
+            .update_from_vision(TELEMETRY.clone())
+            .await;
 
         let mut error_position = position - drivetrain.odometry.position;
         let mut error_angle = (angle - drivetrain.get_angle()).get::<radian>();
@@ -75,11 +74,8 @@ pub async fn follow_path_range(drivetrain: &mut Drivetrain, path: Path, max_err:
 
         let mut speed = error_position;
 
-        let velocity = Vector2::new(setpoint.velocity_x, setpoint.velocity_y);
-        let velocity = velocity.map(|x| x.get::<meter_per_second>());
-
-        let velocity_next = Vector2::new(setpoint.velocity_x, setpoint.velocity_y)
-            .map(|x| x.get::<meter_per_second>());
+        let velocity = setpoint.velocity;
+        let velocity_next = setpoint.velocity;
 
         let acceleration = (velocity_next - velocity) * 1000. / 20.;
 
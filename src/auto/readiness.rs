@@ -0,0 +1,278 @@
+use nalgebra::Vector2;
+use serde::Serialize;
+use uom::si::{angle::degree, f64::Angle};
+
+use crate::{
+    aim::heading::wrap,
+    constants::readiness::*,
+    field::AllianceFlip,
+    input::Ferris,
+    subsystems::{Alarm, IntakeState, Severity},
+    telemetry,
+};
+
+use super::{start::Start, Auto};
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Check {
+    pub name: &'static str,
+    pub go: bool,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, go: bool, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            go,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Everything that has to be right before the match, and whether it is
+#[derive(Serialize, Clone, Debug)]
+pub struct Checklist {
+    pub go: bool,
+    pub checks: Vec<Check>,
+}
+
+/// What the robot knows about itself while it waits for the match
+pub struct Snapshot {
+    pub auto: Auto,
+    pub start: Option<Start>,
+    /// the alliance we're on, which is where the start actually is
+    pub flip: AllianceFlip,
+    /// seconds old and where it put us, in our odometry
+    pub vision: Option<(f64, Vector2<f64>, Angle)>,
+    /// None if something's holding the intake
+    pub intake: Option<IntakeState>,
+    pub staged: Option<bool>,
+    pub alarms: Vec<Alarm>,
+}
+
+impl Checklist {
+    pub fn check(snapshot: &Snapshot) -> Self {
+        let checks = vec![
+            check_start(snapshot),
+            check_intake(snapshot),
+            check_note(snapshot),
+            check_health(snapshot),
+        ];
+
+        Self {
+            go: checks.iter().all(|check| check.go),
+            checks,
+        }
+    }
+}
+
+fn check_start(snapshot: &Snapshot) -> Check {
+    let Some(trajectory) = snapshot.auto.first_trajectory() else {
+        return Check::new("start pose", true, "auto doesn't drive");
+    };
+    let Some(start) = snapshot.start else {
+        return Check::new("start pose", false, format!("couldn't read {trajectory}"));
+    };
+    // where odometry will be seeded, which vision is already in
+    let heading = snapshot.flip.heading(start.heading);
+    let start = snapshot.flip.position(start.position);
+
+    match snapshot.vision {
+        Some((age, position, seen)) if age <= VISION_MAX_AGE => {
+            let off = (position - start).norm();
+            let turned = wrap(seen - heading).get::<degree>().abs();
+            let detail = format!(
+                "{off:.2}m and {turned:.0}° from ({:.2}, {:.2}, {:.0}°), seeing ({:.2}, {:.2}, {:.0}°)",
+                start.x,
+                start.y,
+                heading.get::<degree>(),
+                position.x,
+                position.y,
+                seen.get::<degree>()
+            );
+            let placed = off <= START_TOLERANCE && turned <= START_HEADING_TOLERANCE;
+            Check::new("start pose", placed, detail)
+        }
+        Some((age, ..)) => Check::new("start pose", false, format!("vision is {age:.1}s old")),
+        None => Check::new("start pose", false, "no vision pose"),
+    }
+}
+
+fn check_intake(snapshot: &Snapshot) -> Check {
+    match snapshot.intake {
+        Some(IntakeState::Homed) => Check::new("intake homed", true, "homed"),
+        Some(IntakeState::HomingFailed) => Check::new("intake homed", false, "homing failed"),
+        Some(IntakeState::Homing) => Check::new("intake homed", false, "still homing"),
+        Some(IntakeState::Unhomed) if snapshot.auto.homes_intake() => {
+            Check::new("intake homed", true, "auto homes it first")
+        }
+        Some(IntakeState::Unhomed) => Check::new("intake homed", false, "not homed"),
+        None => Check::new("intake homed", false, "intake busy"),
+    }
+}
+
+fn check_note(snapshot: &Snapshot) -> Check {
+    match snapshot.staged {
+        Some(true) => Check::new("note staged", true, "staged"),
+        Some(false) if !snapshot.auto.scores_preload() => {
+            Check::new("note staged", true, "auto doesn't shoot")
+        }
+        Some(false) => Check::new("note staged", false, "no note in the shooter"),
+        None => Check::new("note staged", false, "note sensors busy"),
+    }
+}
+
+fn check_health(snapshot: &Snapshot) -> Check {
+    let problems: Vec<String> = snapshot
+        .alarms
+        .iter()
+        .filter(|alarm| alarm.severity >= Severity::Error)
+        .map(|alarm| format!("{} {}", alarm.device, alarm.problem))
+        .collect();
+
+    if problems.is_empty() {
+        Check::new("devices healthy", true, "no errors")
+    } else {
+        Check::new("devices healthy", false, problems.join(", "))
+    }
+}
+
+/// run while disabled, for the drive team to read before the match
+pub async fn publish_readiness(robot: &Ferris) {
+    let (auto, vision) = {
+        let telemetry = robot.telemetry.read().await;
+        let vision = telemetry.vision.latest().map(|measurement| {
+            (
+                measurement.captured.elapsed().as_secs_f64(),
+                measurement.position,
+                measurement.heading,
+            )
+        });
+        (telemetry.auto.clone(), vision)
+    };
+
+    let snapshot = Snapshot {
+        start: auto.start(),
+        auto,
+        flip: AllianceFlip::current(),
+        vision,
+        intake: robot.intake.try_borrow().ok().map(|intake| intake.state()),
+        staged: robot.note.try_borrow().ok().map(|note| note.staged()),
+        alarms: robot
+            .health
+            .try_borrow()
+            .map(|health| health.alarms())
+            .unwrap_or_default(),
+    };
+    let checklist = Checklist::check(&snapshot);
+
+    telemetry::put_bool("ready", checklist.go).await;
+    if let Ok(checklist) = serde_json::to_string(&checklist) {
+        telemetry::put_text("readiness", &checklist).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector2;
+    use uom::si::{angle::degree, f64::Angle};
+
+    use super::{Checklist, Snapshot};
+    use crate::{
+        auto::{start::Start, Auto},
+        field::{AllianceFlip, FieldPose},
+        subsystems::{Alarm, IntakeState, Severity},
+    };
+
+    const START: Vector2<f64> = Vector2::new(0.469, 7.034497);
+
+    fn ready(auto: Auto) -> Snapshot {
+        let start = auto.first_trajectory().map(|_| Start {
+            position: START,
+            heading: Angle::new::<degree>(0.),
+        });
+        Snapshot {
+            vision: start.map(|start| (0.1, start.position, start.heading)),
+            start,
+            auto,
+            flip: AllianceFlip::for_alliance(false),
+            intake: Some(IntakeState::Unhomed),
+            staged: Some(true),
+            alarms: vec![Alarm::new("fr drive", "hot", Severity::Warning)],
+        }
+    }
+
+    #[test]
+    fn go_when_placed_right() {
+        let checklist = Checklist::check(&ready(Auto::TopStop));
+        assert!(checklist.go, "{checklist:?}");
+    }
+
+    #[test]
+    fn go_when_placed_right_on_red() {
+        let red = AllianceFlip::for_alliance(true);
+        let mut snapshot = ready(Auto::TopStop);
+        snapshot.flip = red;
+
+        // the cameras see blue's start mirrored onto red's half, in field
+        // coordinates, and ingesting puts it in our odometry
+        let seen = red.mirror(FieldPose::new(START.x, START.y, Angle::new::<degree>(0.)));
+        let seen = red.from_field(seen);
+        snapshot.vision = Some((0.1, seen.position, seen.heading));
+        let checklist = Checklist::check(&snapshot);
+        assert!(checklist.go, "{checklist:?}");
+
+        // blue's spot as drawn isn't where red starts
+        snapshot.vision = Some((0.1, START, seen.heading));
+        assert!(!Checklist::check(&snapshot).go);
+    }
+
+    #[test]
+    fn no_go_when_misplaced_or_stale() {
+        let mut snapshot = ready(Auto::TopStop);
+        let straight = Angle::new::<degree>(0.);
+        snapshot.vision = Some((0.1, Vector2::new(0.469, 6.5), straight));
+        assert!(!Checklist::check(&snapshot).go);
+
+        snapshot.vision = Some((5., START, straight));
+        assert!(!Checklist::check(&snapshot).go);
+
+        snapshot.vision = None;
+        let checklist = Checklist::check(&snapshot);
+        assert_eq!(checklist.checks.iter().filter(|check| !check.go).count(), 1);
+
+        snapshot.vision = Some((0.1, START, straight));
+        snapshot.start = None;
+        assert!(!Checklist::check(&snapshot).go);
+    }
+
+    #[test]
+    fn no_go_when_turned() {
+        let mut snapshot = ready(Auto::TopStop);
+        snapshot.vision = Some((0.1, START, Angle::new::<degree>(30.)));
+        assert!(!Checklist::check(&snapshot).go);
+
+        // the same heading as 5°, close enough
+        snapshot.vision = Some((0.1, START, Angle::new::<degree>(-355.)));
+        assert!(Checklist::check(&snapshot).go);
+    }
+
+    #[test]
+    fn no_go_on_errors_or_missing_note() {
+        let mut snapshot = ready(Auto::BottomClose);
+        snapshot
+            .alarms
+            .push(Alarm::new("feeder", "not responding", Severity::Critical));
+        assert!(!Checklist::check(&snapshot).go);
+
+        let mut snapshot = ready(Auto::BottomClose);
+        snapshot.staged = Some(false);
+        assert!(!Checklist::check(&snapshot).go);
+
+        // nothing to shoot, and it homes the intake itself
+        let mut snapshot = ready(Auto::ZeroIntake);
+        snapshot.staged = Some(false);
+        assert!(Checklist::check(&snapshot).go);
+    }
+}
@@ -0,0 +1,186 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    sync::Mutex,
+    time::Duration,
+};
+
+use lazy_static::lazy_static;
+use nalgebra::Vector2;
+use uom::si::{
+    angle::degree,
+    f64::{Angle, Time},
+    time::second,
+};
+use wpi_trajectory::Path;
+
+use crate::{
+    constants::auto::*,
+    field::AllianceFlip,
+    subsystems::Drivetrain,
+    telemetry::{Pose, TelemetryStore},
+    vision::Measurement,
+};
+
+use super::{path::sample, Auto};
+
+/// where choreo trajectories are deployed on the rio
+pub fn trajectory_path(name: &str) -> String {
+    format!("/home/lvuser/deploy/choreo/{}.traj", name)
+}
+
+lazy_static! {
+    /// trajectories don't change once deployed, and the checklist asks every loop
+    static ref STARTS: Mutex<HashMap<&'static str, Start>> = Default::default();
+    /// missing trajectories we've already complained about
+    static ref MISSING: Mutex<HashSet<&'static str>> = Default::default();
+}
+
+/// The first sample of a routine's first trajectory, as drawn on the blue side
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Start {
+    /// meters
+    pub position: Vector2<f64>,
+    /// choreo's heading
+    pub heading: Angle,
+}
+
+impl Start {
+    /// read from `name`.traj, None if it's missing or won't parse
+    ///
+    /// Only kept once it's been read, so a trajectory deployed after boot
+    /// still turns up
+    pub fn load(name: &'static str) -> Option<Self> {
+        let mut starts = STARTS.lock().ok()?;
+        if let Some(start) = starts.get(name) {
+            return Some(*start);
+        }
+
+        let Some(start) = Self::read(name) else {
+            if MISSING.lock().is_ok_and(|mut missing| missing.insert(name)) {
+                println!("couldn't read the start of {}", trajectory_path(name));
+            }
+            return None;
+        };
+        starts.insert(name, start);
+        Some(start)
+    }
+
+    fn read(name: &str) -> Option<Self> {
+        let trajectory = fs::read_to_string(trajectory_path(name)).ok()?;
+        let path = Path::from_trajectory(&trajectory).ok()?;
+        let first = sample(&path, Time::new::<second>(0.));
+
+        Some(Self {
+            position: first.position,
+            heading: first.heading,
+        })
+    }
+
+    /// what the drive team sees, flipped the way the paths will be
+    pub fn pose(&self, flip: &AllianceFlip) -> Pose {
+        let position = flip.position(self.position);
+
+        Pose {
+            x: position.x,
+            y: position.y,
+            theta: flip.heading(self.heading).get::<degree>(),
+        }
+    }
+
+    /// what the gyro should read here, in the sense `follow_path` steers by
+    pub fn angle(&self, flip: &AllianceFlip) -> Angle {
+        -flip.heading(self.heading)
+    }
+}
+
+/// Seed odometry and the gyro from where `auto` starts, and hand back the
+/// trajectory it starts on for the routine to drive first
+///
+/// A fresh, precise vision pose wins for position, since it's where the
+/// robot actually is rather than where it was meant to be put. Heading
+/// always comes from the trajectory
+pub async fn place(
+    auto: &Auto,
+    drivetrain: &mut Drivetrain,
+    telemetry: &TelemetryStore,
+) -> Option<&'static str> {
+    let flip = AllianceFlip::current();
+    let start = auto.start();
+
+    let vision = telemetry.read().await.vision.latest().cloned();
+    let vision = vision.filter(seeds);
+
+    // vision is already in our odometry, the start is drawn for blue
+    match (vision, start) {
+        (Some(measurement), _) => {
+            let position = measurement.position;
+            println!(
+                "seeding {} from vision at ({:.2}, {:.2})",
+                auto.id(),
+                position.x,
+                position.y
+            );
+            drivetrain.odometry.reset(position);
+        }
+        (None, Some(start)) => drivetrain.odometry.set(start.position),
+        (None, None) => println!("no start for {}, leaving odometry", auto.id()),
+    }
+
+    let angle = start.map_or(Angle::new::<degree>(0.), |start| start.angle(&flip));
+    drivetrain.seed_angle(angle);
+
+    auto.first_trajectory()
+}
+
+/// fresh and precise enough to place the robot by
+fn seeds(measurement: &Measurement) -> bool {
+    measurement.captured.elapsed() <= Duration::from_secs_f64(VISION_SEED_MAX_AGE)
+        && measurement.position_error() <= VISION_SEED_STD_DEV
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use nalgebra::Vector2;
+    use uom::si::{angle::degree, f64::Angle};
+
+    use super::seeds;
+    use crate::vision::{Measurement, Sighting};
+
+    /// what the cameras send for `tags` tags this far away
+    fn measurement(tags: usize, distance: f64) -> Measurement {
+        let base = if tags > 1 { 0.3 } else { 0.9 };
+        let std_dev = base * (1. + distance * distance / 30.);
+
+        Measurement {
+            captured: Instant::now(),
+            tags: vec![
+                Sighting {
+                    id: 7,
+                    distance,
+                    ambiguity: 0.1,
+                };
+                tags
+            ],
+            position: Vector2::new(1.5, 5.5),
+            heading: Angle::new::<degree>(180.),
+            position_std_dev: Vector2::new(std_dev, std_dev),
+            heading_std_dev: Angle::new::<degree>(15.),
+        }
+    }
+
+    #[test]
+    fn seeds_from_what_the_cameras_send() {
+        assert!(seeds(&measurement(2, 1.5)));
+        assert!(seeds(&measurement(3, 3.)));
+
+        assert!(!seeds(&measurement(2, 5.)));
+        assert!(!seeds(&measurement(1, 0.5)));
+
+        let mut stale = measurement(2, 1.5);
+        stale.captured -= Duration::from_secs(2);
+        assert!(!seeds(&stale));
+    }
+}
@@ -1,4 +1,8 @@
 pub const FPS_LIMIT: f64 = 250.;
+/// seconds without a fed loop before every motor is put in neutral
+pub const WATCHDOG_TIMEOUT: f64 = 0.1;
+/// seconds of steady loops after a stall before its alarm clears
+pub const WATCHDOG_RECOVERY: f64 = 5.;
 pub const FR_DRIVE: i32 = 1;
 pub const FR_TURN: i32 = 2;
 pub const FR_ENCODER: i32 = 3;
@@ -41,23 +45,188 @@ pub const INDICATOR_PORT_LEFT: i32 = 4;
 pub const INDICATOR_PORT_RIGHT: i32 = 5;
 
 pub const TELEMETRY_PORT: i32 = 5807;
-pub const HALF_FIELD_WIDTH_METERS: f64 = 4.1148; // 54/4 feet
-pub const HALF_FIELD_LENGTH_METERS: f64 = 8.2296; // 54/2 feet
+/// meters, red's odometry y is this minus blue's (54/4 feet). Not half the
+/// field's width, the field's size comes from the apriltag layout
+pub const RED_REFLECTION_METERS: f64 = 4.1148;
+
+pub mod scheduler {
+    /// hz, the control task runs at `FPS_LIMIT`
+    pub const POWER_RATE: f64 = 50.;
+    pub const TELEMETRY_RATE: f64 = 50.;
+
+    /// milliseconds, upper edge of each timing histogram bucket
+    pub const HISTOGRAM_BUCKETS: [f64; 7] = [0.25, 0.5, 1., 2., 4., 8., 16.];
+}
+
+pub mod field {
+    /// blue speaker opening, choreo coordinates
+    pub const SPEAKER_X: f64 = 0.;
+    pub const SPEAKER_Y: f64 = 5.548;
+}
 
 pub mod intake {
     pub const INTAKE_OCCUPIED_CURRENT: f64 = 20.;
     pub const INTAKE_OCCUPIED_VELOCITY: f64 = 2000.;
     /// velocity that intake acceleration is "over" at
     pub const INTAKE_FREE_VELOCITY: f64 = 2000.;
+    /// seconds for the rollers to come up to speed before grabbing
+    pub const INTAKE_SPIN_UP_TIMEOUT: f64 = 1.;
+    /// seconds to wait for a note once the intake is down and spinning
+    pub const INTAKE_GRAB_TIMEOUT: f64 = 8.;
 
     pub const INTAKE_DOWN_THRESHOLD: f64 = -67.;
     pub const INTAKE_UP_THRESHOLD: f64 = 4.7;
     pub const INTAKE_DOWN_GOAL: f64 = -70.;
     pub const INTAKE_UP_GOAL: f64 = 7.6;
 
-    pub const INTAKE_ZERO_POINT: f64 = 3.;
+    /// degrees, where the intake sits when the limit switch trips: 3 motor
+    /// degrees through the 41.6:1 actuate gearing
+    pub const INTAKE_ZERO_POINT: f64 = 3. / 41.6;
+    /// degrees, where the intake sits against the hard stop above the limit switch
+    pub const INTAKE_HARD_STOP_POINT: f64 = 11.;
+    pub const INTAKE_HOMING_SPEED: f64 = 0.3;
+    pub const INTAKE_BACKOFF_SPEED: f64 = 0.15;
+    /// amps, stalled on the hard stop
+    pub const INTAKE_HOMING_CURRENT: f64 = 30.;
+    /// seconds
+    pub const INTAKE_HOMING_TIMEOUT: f64 = 4.;
+
+    pub const INTAKE_DEGREES_PER_SECOND: f64 = 870.;
+    pub const INTAKE_DEGREES_PER_SECOND_SQUARED: f64 = 4000.;
+
+    /// actuate position (degrees) where the intake sticks straight out. Not
+    /// measured yet: home it, hold it level against a square and read
+    /// `intake position` off telemetry
+    pub const INTAKE_HORIZONTAL: f64 = 0.;
+    /// volts
+    pub const INTAKE_KS: f64 = 0.15;
+    /// volts to hold the intake horizontal, no gravity feedforward until
+    /// `INTAKE_HORIZONTAL` has been measured
+    pub const INTAKE_KG: f64 = 0.;
+    /// volts per radian per second
+    pub const INTAKE_KV: f64 = 0.3;
+    /// volts per radian per second squared
+    pub const INTAKE_KA: f64 = 0.01;
+    /// volts per degree, the spark's old onboard p of 0.08 per motor rotation
+    pub const INTAKE_KP: f64 = 0.11;
+    /// volts per degree per second, its onboard d of 0.45 over a 1ms loop
+    pub const INTAKE_KD: f64 = 0.0006;
+}
+
+pub mod health {
+    /// seconds between polling every device
+    pub const POLL_PERIOD: f64 = 0.5;
+    /// celsius
+    pub const MOTOR_TEMPERATURE_WARNING: f64 = 70.;
+    pub const MOTOR_TEMPERATURE_CRITICAL: f64 = 90.;
+    /// amps
+    pub const MOTOR_CURRENT_WARNING: f64 = 60.;
+}
+
+pub mod auto {
+    /// meters, a vision pose at least this precise places the robot instead
+    /// of the auto's first trajectory. The cameras claim 0.3m for two or more
+    /// tags, growing with distance, so this is multiple tags within about 3m.
+    /// One tag never is
+    pub const VISION_SEED_STD_DEV: f64 = 0.4;
+    /// seconds
+    pub const VISION_SEED_MAX_AGE: f64 = 0.5;
+}
+
+pub mod readiness {
+    /// meters the robot can be from an auto's start and still run it
+    pub const START_TOLERANCE: f64 = 0.15;
+    /// degrees the robot can be turned from an auto's start heading
+    pub const START_HEADING_TOLERANCE: f64 = 10.;
+    /// seconds before a vision pose is too old to check the start against
+    pub const VISION_MAX_AGE: f64 = 1.;
+}
+
+pub mod vision {
+    /// measurements kept per camera
+    pub const BUFFER_LENGTH: usize = 50;
+    /// seconds between capture and sending before an observation is refused
+    pub const MAX_AGE: f64 = 0.5;
+    /// meters a pose can be outside the field and still be believed
+    pub const FIELD_MARGIN: f64 = 0.5;
+    /// seconds of odometry kept to bring a measurement up to date, longer
+    /// than `MAX_AGE` to leave room for the network
+    pub const ODOMETRY_HISTORY: f64 = 1.;
+    /// meters, odometry only moves to a measurement at least this precise,
+    /// the same multiple tags within about 3m that can seed an auto
+    pub const ODOMETRY_STD_DEV: f64 = 0.4;
+}
+
+pub mod power {
+    /// amps, stator current limits set on every talon at startup
+    pub const DRIVE_CURRENT_LIMIT: f64 = 60.;
+    pub const TURN_CURRENT_LIMIT: f64 = 30.;
+
+    /// volts, percent output is scaled as if the battery always read this
+    pub const NOMINAL_VOLTAGE: f64 = 12.;
+
+    /// volts, below this drive output gets scaled back
+    pub const SAG_VOLTAGE: f64 = 9.5;
+    /// volts, below this non-critical mechanisms are slowed too
+    pub const SHED_VOLTAGE: f64 = 8.;
+    /// volts above a threshold before we step back up
+    pub const RECOVERY_HYSTERESIS: f64 = 0.5;
+    /// seconds the voltage has to stay recovered
+    pub const RECOVERY_TIME: f64 = 0.5;
+
+    pub const SAG_DRIVE_SCALE: f64 = 0.8;
+    pub const SHED_DRIVE_SCALE: f64 = 0.6;
+    /// the intake rollers, amp bar and climbers, while shedding
+    pub const SHED_MECHANISM_SCALE: f64 = 0.5;
+
+    /// amps, smart current limits burned into each spark with the REV
+    /// hardware client, frcrs can't set them
+    pub const FEEDER_CURRENT_LIMIT: f64 = 40.;
+    pub const FLYWHEEL_CURRENT_LIMIT: f64 = 60.;
+    pub const INTAKE_ROLLER_CURRENT_LIMIT: f64 = 40.;
+    /// above `intake::INTAKE_HOMING_CURRENT`, or homing never sees the stall
+    pub const INTAKE_ACTUATE_CURRENT_LIMIT: f64 = 40.;
+    /// above `amp::HOMING_CURRENT`
+    pub const AMP_BAR_CURRENT_LIMIT: f64 = 30.;
+    /// above `climber::STALL_CURRENT`
+    pub const CLIMBER_CURRENT_LIMIT: f64 = 60.;
+    /// amps past its limit before a spark counts as never having been limited
+    pub const CURRENT_LIMIT_MARGIN: f64 = 10.;
 
-    pub const INTAKE_DEGREES_PER_SECOND: f64 = 145.;
+    /// seconds `neutral_all` waits for the motors to be stopped
+    pub const NEUTRAL_TIMEOUT: f64 = 0.02;
+}
+
+pub mod distribution {
+    /// seconds between reading every channel
+    pub const SAMPLE_PERIOD: f64 = 0.02;
+}
+
+pub mod jam {
+    /// amps
+    pub const FEEDER_JAM_CURRENT: f64 = 25.;
+    /// rpm
+    pub const FEEDER_JAM_VELOCITY: f64 = 300.;
+    pub const ROLLER_JAM_CURRENT: f64 = 35.;
+    pub const ROLLER_JAM_VELOCITY: f64 = 500.;
+    /// seconds stuck before it counts as a jam
+    pub const JAM_TIME: f64 = 0.3;
+
+    /// times to back the note out and try again before giving up
+    pub const JAM_RETRIES: usize = 2;
+    /// seconds
+    pub const REVERSE_TIME: f64 = 0.25;
+    pub const RESEAT_TIME: f64 = 0.3;
+    pub const EJECT_TIME: f64 = 0.75;
+}
+
+pub mod note {
+    /// seconds a sensor has to agree with itself before we believe it
+    pub const STALL_DEBOUNCE: f64 = 0.1;
+    pub const CAM_DEBOUNCE: f64 = 0.04;
+    pub const BEAM_DEBOUNCE: f64 = 0.02;
+    /// seconds a handoff from the intake to the shooter should take at most
+    pub const TRANSIT_TIMEOUT: f64 = 1.;
 }
 
 pub mod drivetrain {
@@ -78,9 +247,91 @@ pub mod drivetrain {
     pub const SWERVE_DRIVE_IE: f64 = 0.0; //0.175; // integral enable
 
     pub const PODIUM_SHOT_ANGLE: f64 = 34.34; // degrees
+
+    pub const AIM_KP: f64 = 0.45;
+    pub const AIM_MAX_VELOCITY: f64 = 360.; // degrees per second
+    pub const AIM_MAX_ACCELERATION: f64 = 900.; // degrees per second squared
+    pub const AIM_TOLERANCE: f64 = 2.5; // degrees
+    /// direction the shooter faces, relative to robot forward
+    pub const SHOOTER_FACING: f64 = 180.; // degrees
+}
+
+pub mod shooter {
+    pub const FLYWHEEL_DIAMETER: f64 = 0.1016; // meters, 4in
+    /// fraction of flywheel surface speed the note leaves at
+    pub const NOTE_EXIT_EFFICIENCY: f64 = 0.45;
+    pub const SHOT_PITCH: f64 = 55.; // degrees above horizontal
+
+    /// seconds for each step of handing a note from the intake to the feeder
+    pub const HANDOFF_TIMEOUT: f64 = 1.5;
+    /// seconds to pull a note onto the beam break with the feeder alone
+    pub const LOAD_TIMEOUT: f64 = 2.;
+
+    /// feeder output while creeping the note off the beam break
+    pub const INDEX_CREEP_SPEED: f64 = 0.1;
+    /// feeder revolutions past where the beam cleared to park the note
+    pub const INDEX_DISTANCE: f64 = 0.5;
+    pub const INDEX_TOLERANCE: f64 = 0.05;
+    /// revolutions, a note stopped this close has parked once it settles
+    pub const INDEX_SETTLE_TOLERANCE: f64 = 0.15;
+    /// revolutions it can drift and still count as settled
+    pub const INDEX_SETTLE_DISTANCE: f64 = 0.01;
+    /// seconds
+    pub const INDEX_SETTLE_TIME: f64 = 0.1;
+    /// seconds
+    pub const INDEX_TIMEOUT: f64 = 1.;
+    /// output per revolution from where the note should park
+    pub const FEEDER_KP: f64 = 0.1;
+    /// output it takes to get the feeder turning
+    pub const FEEDER_KS: f64 = 0.03;
 }
 
 pub mod amp {
+    // revolutions from the stowed hard stop
     pub const STOWED_POSITION: f64 = -3.;
     pub const DEPLOYED_POSITION: f64 = -30.4;
+
+    pub const SOFT_LIMIT_STOWED: f64 = -1.;
+    pub const SOFT_LIMIT_DEPLOYED: f64 = -31.5;
+
+    pub const HOMING_SPEED: f64 = 0.15;
+    pub const HOMING_CURRENT: f64 = 15.;
+    /// rpm, below this counts as stalled against the hard stop
+    pub const HOMING_STALL_VELOCITY: f64 = 50.;
+    /// seconds before stall detection kicks in, so spinning up doesn't count
+    pub const HOMING_GRACE: f64 = 0.25;
+    pub const HOMING_TIMEOUT: f64 = 3.; // seconds
+
+    pub const MAX_VELOCITY: f64 = 60.; // revolutions per second
+    pub const MAX_ACCELERATION: f64 = 240.; // revolutions per second squared
+    pub const MAX_JERK: f64 = 2400.; // revolutions per second cubed
+    pub const TOLERANCE: f64 = 0.75; // revolutions
+}
+
+pub mod climber {
+    // revolutions of arm extension from the bottom hard stop
+    pub const SOFT_LIMIT_TOP: f64 = 95.;
+    pub const SOFT_LIMIT_BOTTOM: f64 = 1.;
+
+    pub const HOMING_SPEED: f64 = 0.2;
+    pub const HOMING_CURRENT: f64 = 25.;
+    pub const HOMING_TIMEOUT: f64 = 6.; // seconds
+
+    /// amps, held this long with the arm not moving we cut power
+    pub const STALL_CURRENT: f64 = 50.;
+    pub const STALL_VELOCITY: f64 = 30.; // rpm
+    pub const STALL_TIME: f64 = 0.75; // seconds
+
+    pub const CLIMB_SPEED: f64 = 0.8;
+    /// output per degree of roll, positive roll speeds up the left arm
+    pub const LEVEL_KP: f64 = 0.04;
+    pub const LEVEL_MAX_CORRECTION: f64 = 0.3;
+
+    /// extension to reach up for the chain
+    pub const READY_EXTENSION: f64 = 90.;
+    pub const MAX_VELOCITY: f64 = 60.; // revolutions per second
+    pub const MAX_ACCELERATION: f64 = 150.; // revolutions per second squared
+    pub const KP: f64 = 0.1;
+    pub const KV: f64 = 1. / 94.; // output per revolution per second
+    pub const TOLERANCE: f64 = 1.5; // revolutions
 }
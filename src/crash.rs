@@ -0,0 +1,230 @@
+use std::{
+    backtrace::Backtrace,
+    collections::HashMap,
+    fs,
+    panic::{self, PanicInfo},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    subsystems::{Alarm, Severity},
+    telemetry::{Data, TELEMETRY},
+};
+
+/// where crash reports are kept on the rio
+pub const DIRECTORY: &str = "/home/lvuser/crashes";
+/// older reports are deleted past this many
+const MAX_REPORTS: usize = 10;
+
+lazy_static! {
+    static ref STARTED: Instant = Instant::now();
+    /// where every panic we lived through happened
+    static ref PANICS: Mutex<Vec<Alarm>> = Mutex::new(Vec::new());
+}
+
+/// a panic was added to `PANICS` since `caught_panics` last looked
+static NEW_PANIC: AtomicBool = AtomicBool::new(false);
+/// tells apart reports from the same millisecond
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CrashReport {
+    /// milliseconds since the unix epoch
+    pub time: u128,
+    /// counts up from startup, for reports in the same millisecond
+    #[serde(default)]
+    pub sequence: u64,
+    /// seconds since robot code started
+    pub uptime: f64,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+    /// telemetry as it was when we crashed, if we could get at it
+    pub telemetry: HashMap<String, Data>,
+}
+
+impl CrashReport {
+    fn new(info: &PanicInfo) -> Self {
+        let message = if let Some(message) = info.payload().downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = info.payload().downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown panic".to_owned()
+        };
+
+        // the lock might be held by whatever panicked, so don't wait on it
+        let telemetry = TELEMETRY
+            .try_read()
+            .map(|telemetry| telemetry.data.clone())
+            .unwrap_or_default();
+
+        Self {
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_millis()),
+            sequence: SEQUENCE.fetch_add(1, Ordering::SeqCst),
+            uptime: STARTED.elapsed().as_secs_f64(),
+            message,
+            location: info.location().map(|location| location.to_string()),
+            backtrace: Backtrace::force_capture().to_string(),
+            telemetry,
+        }
+    }
+}
+
+/// Write a crash report on any panic, on any thread, and remember where it
+/// happened for `caught_panics`
+///
+/// Most panics are caught, by tokio for a spawned task or the telemetry
+/// server, and the control loop carries on, so this leaves the motors alone.
+/// A panicked auto ends its task, which `Ferris::lifecycle` stops everything
+/// for, teleop sequences stop their own on the way out, and `entrypoint`
+/// stops them if the panic takes the control loop down
+pub fn install() {
+    lazy_static::initialize(&STARTED);
+
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let report = CrashReport::new(info);
+        if let Err(e) = write(Path::new(DIRECTORY), &report) {
+            println!("couldn't write crash report: {e}");
+        }
+        record(&report);
+
+        previous(info);
+    }));
+}
+
+fn record(report: &CrashReport) {
+    // whoever panicked might hold it, don't wait
+    let Ok(mut panics) = PANICS.try_lock() else {
+        return;
+    };
+
+    let location = report.location.as_deref().unwrap_or("unknown");
+    if !panics.iter().any(|alarm| alarm.device == location) {
+        panics.push(Alarm::new(location, "panicked", Severity::Error));
+    }
+    NEW_PANIC.store(true, Ordering::SeqCst);
+}
+
+/// every panic so far, if there's been a new one since the last call
+pub fn caught_panics() -> Option<Vec<Alarm>> {
+    if !NEW_PANIC.swap(false, Ordering::SeqCst) {
+        return None;
+    }
+    PANICS.lock().ok().map(|panics| panics.clone())
+}
+
+/// save a report, deleting the oldest past `MAX_REPORTS`
+pub fn write(directory: &Path, report: &CrashReport) -> std::io::Result<()> {
+    fs::create_dir_all(directory)?;
+    let name = format!("{}-{}.json", report.time, report.sequence);
+    fs::write(directory.join(name), serde_json::to_string(report)?)?;
+
+    let names = report_names(directory)?;
+    let excess = names.len().saturating_sub(MAX_REPORTS);
+    for name in &names[..excess] {
+        fs::remove_file(directory.join(name))?;
+    }
+
+    Ok(())
+}
+
+/// every report we still have, newest first
+pub fn recent(directory: &Path) -> Vec<CrashReport> {
+    let Ok(names) = report_names(directory) else {
+        return Vec::new();
+    };
+
+    names
+        .iter()
+        .rev()
+        .filter_map(|name| fs::read_to_string(directory.join(name)).ok())
+        .filter_map(|buf| serde_json::from_str(&buf).ok())
+        .collect()
+}
+
+/// report file names, oldest first
+fn report_names(directory: &Path) -> std::io::Result<Vec<String>> {
+    let mut names: Vec<(u128, u64, String)> = fs::read_dir(directory)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter_map(|name| {
+            let stem = name.strip_suffix(".json")?;
+            // older reports are just the time
+            let (time, sequence) = stem.split_once('-').unwrap_or((stem, "0"));
+            Some((time.parse().ok()?, sequence.parse().ok()?, name))
+        })
+        .collect();
+
+    names.sort();
+    Ok(names.into_iter().map(|(_, _, name)| name).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+
+    use super::{recent, write, CrashReport, MAX_REPORTS};
+
+    fn report(time: u128) -> CrashReport {
+        CrashReport {
+            time,
+            sequence: 0,
+            uptime: 1.,
+            message: format!("crash {time}"),
+            location: None,
+            backtrace: String::new(),
+            telemetry: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn keeps_newest_reports() {
+        let directory = std::env::temp_dir().join("crash_reports_test");
+        let _ = fs::remove_dir_all(&directory);
+
+        for time in 0..MAX_REPORTS as u128 + 3 {
+            write(&directory, &report(1_700_000_000_000 + time)).unwrap();
+        }
+
+        let reports = recent(&directory);
+        assert_eq!(reports.len(), MAX_REPORTS);
+        assert_eq!(reports[0].time, 1_700_000_000_000 + MAX_REPORTS as u128 + 2);
+        assert_eq!(reports.last().unwrap().time, 1_700_000_000_003);
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn orders_by_time_not_name() {
+        let directory = std::env::temp_dir().join("crash_reports_order_test");
+        let _ = fs::remove_dir_all(&directory);
+
+        // same millisecond, and a time with fewer digits
+        for sequence in 0..3 {
+            let report = CrashReport {
+                sequence,
+                ..report(1_000)
+            };
+            write(&directory, &report).unwrap();
+        }
+        write(&directory, &report(999)).unwrap();
+
+        let reports = recent(&directory);
+        let order: Vec<_> = reports.iter().map(|r| (r.time, r.sequence)).collect();
+        assert_eq!(order, vec![(1_000, 2), (1_000, 1), (1_000, 0), (999, 0)]);
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
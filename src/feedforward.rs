@@ -0,0 +1,72 @@
+use uom::si::{angle::radian, f64::Angle};
+
+/// Voltage to hold and move a pivoting arm against gravity
+///
+/// `angle` is measured from horizontal, velocity and acceleration are in
+/// radians per second (squared)
+#[derive(Clone, Copy, Debug)]
+pub struct ArmFeedforward {
+    /// volts to overcome static friction
+    pub ks: f64,
+    /// volts to hold the arm horizontal
+    pub kg: f64,
+    /// volts per radian per second
+    pub kv: f64,
+    /// volts per radian per second squared
+    pub ka: f64,
+}
+
+impl ArmFeedforward {
+    pub const fn new(ks: f64, kg: f64, kv: f64, ka: f64) -> Self {
+        Self { ks, kg, kv, ka }
+    }
+
+    pub fn calculate(&self, angle: Angle, velocity: f64, acceleration: f64) -> f64 {
+        // f64::signum is 1 at zero
+        let friction = if velocity == 0. {
+            0.
+        } else {
+            self.ks * velocity.signum()
+        };
+
+        friction
+            + self.kg * angle.get::<radian>().cos()
+            + self.kv * velocity
+            + self.ka * acceleration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uom::si::{angle::degree, f64::Angle};
+
+    use super::ArmFeedforward;
+
+    const FF: ArmFeedforward = ArmFeedforward::new(0.1, 0.5, 1., 0.2);
+
+    #[test]
+    fn holds_against_gravity() {
+        let horizontal = FF.calculate(Angle::new::<degree>(0.), 0., 0.);
+        let vertical = FF.calculate(Angle::new::<degree>(90.), 0., 0.);
+        let hanging = FF.calculate(Angle::new::<degree>(180.), 0., 0.);
+
+        assert!((horizontal - 0.5).abs() < 1e-9);
+        assert!(vertical.abs() < 1e-9);
+        assert!((hanging + 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn static_friction_follows_direction() {
+        let up = FF.calculate(Angle::new::<degree>(90.), 1., 0.);
+        let down = FF.calculate(Angle::new::<degree>(90.), -1., 0.);
+
+        assert!((up - 1.1).abs() < 1e-9);
+        assert!((down + 1.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn acceleration_adds() {
+        let accelerating = FF.calculate(Angle::new::<degree>(90.), 0., 2.);
+        assert!((accelerating - 0.4).abs() < 1e-9);
+    }
+}
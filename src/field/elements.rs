@@ -0,0 +1,250 @@
+use nalgebra::Vector2;
+use uom::si::f64::Angle;
+
+use super::{
+    layout::{Layout, LAYOUT},
+    AllianceFlip,
+};
+
+/// which one we're on comes from `AllianceFlip::alliance`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Alliance {
+    Blue,
+    Red,
+}
+
+impl Alliance {
+    pub fn from_red(red: bool) -> Self {
+        if red {
+            Self::Red
+        } else {
+            Self::Blue
+        }
+    }
+
+    pub fn opponent(&self) -> Self {
+        match self {
+            Self::Blue => Self::Red,
+            Self::Red => Self::Blue,
+        }
+    }
+}
+
+/// A spot on the field, in the layout's coordinates: meters from the blue
+/// wall, counterclockwise from facing red. `AllianceFlip::from_field` puts
+/// it in our odometry
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FieldPose {
+    pub position: Vector2<f64>,
+    pub heading: Angle,
+}
+
+impl FieldPose {
+    pub fn new(x: f64, y: f64, heading: Angle) -> Self {
+        Self {
+            position: Vector2::new(x, y),
+            heading,
+        }
+    }
+}
+
+/// Where the pieces of the field are, per alliance
+///
+/// Scoring locations come straight from their tags, so a measured layout
+/// moves them too. Everything else is measured off the drawings for blue
+/// and mirrored for red. Everything is in field coordinates
+pub struct Field<'a> {
+    layout: &'a Layout,
+}
+
+/// blue's stage legs and center, from the drawings
+const STAGE: [(f64, f64); 3] = [(3.2195, 4.1051), (5.6102, 5.3956), (5.6102, 2.8153)];
+const STAGE_CENTER: (f64, f64) = (4.8908, 4.1051);
+
+/// blue's wing notes, nearest the amp first
+const SPIKE_X: f64 = 2.8956;
+const SPIKE_Y: [f64; 3] = [7.0012, 5.5534, 4.1056];
+
+/// the midline notes, from the amp side
+const CENTERLINE_Y: [f64; 5] = [7.4584, 5.7820, 4.1056, 2.4292, 0.7528];
+
+impl Field<'static> {
+    /// the layout deployed to the rio
+    pub fn deployed() -> Self {
+        Self::new(&LAYOUT)
+    }
+}
+
+impl<'a> Field<'a> {
+    pub fn new(layout: &'a Layout) -> Self {
+        Self { layout }
+    }
+
+    pub fn layout(&self) -> &Layout {
+        self.layout
+    }
+
+    /// the tag on the center of our speaker, facing out into the field
+    pub fn speaker(&self, alliance: Alliance) -> Option<FieldPose> {
+        self.tag(match alliance {
+            Alliance::Blue => 7,
+            Alliance::Red => 4,
+        })
+    }
+
+    pub fn amp(&self, alliance: Alliance) -> Option<FieldPose> {
+        self.tag(match alliance {
+            Alliance::Blue => 6,
+            Alliance::Red => 5,
+        })
+    }
+
+    /// between the two tags of the source we take notes from, which sits in
+    /// the opponent's corner
+    pub fn source(&self, alliance: Alliance) -> Option<FieldPose> {
+        let (left, right) = match alliance {
+            Alliance::Blue => (self.tag(1)?, self.tag(2)?),
+            Alliance::Red => (self.tag(9)?, self.tag(10)?),
+        };
+
+        Some(FieldPose {
+            position: (left.position + right.position) / 2.,
+            heading: left.heading,
+        })
+    }
+
+    /// the stage's three legs, podium leg first
+    pub fn stage(&self, alliance: Alliance) -> [Vector2<f64>; 3] {
+        STAGE.map(|(x, y)| {
+            self.mirror(alliance, FieldPose::new(x, y, Angle::default()))
+                .position
+        })
+    }
+
+    pub fn stage_center(&self, alliance: Alliance) -> Vector2<f64> {
+        let (x, y) = STAGE_CENTER;
+        self.mirror(alliance, FieldPose::new(x, y, Angle::default()))
+            .position
+    }
+
+    /// whether `position` is under the stage, where the chain is
+    pub fn under_stage(&self, alliance: Alliance, position: Vector2<f64>) -> bool {
+        let [a, b, c] = self.stage(alliance);
+        let side = |from: Vector2<f64>, to: Vector2<f64>| {
+            let edge = to - from;
+            let offset = position - from;
+            edge.x * offset.y - edge.y * offset.x
+        };
+
+        let sides = [side(a, b), side(b, c), side(c, a)];
+        sides.iter().all(|side| *side >= 0.) || sides.iter().all(|side| *side <= 0.)
+    }
+
+    /// the three notes on `alliance`'s wing, nearest the amp first
+    pub fn spike_notes(&self, alliance: Alliance) -> [Vector2<f64>; 3] {
+        SPIKE_Y.map(|y| {
+            self.mirror(alliance, FieldPose::new(SPIKE_X, y, Angle::default()))
+                .position
+        })
+    }
+
+    /// the five notes on the midline, shared by both alliances
+    pub fn centerline_notes(&self) -> [Vector2<f64>; 5] {
+        CENTERLINE_Y.map(|y| Vector2::new(self.layout.length / 2., y))
+    }
+
+    /// `Layout::load` makes sure the deployed one has every tag, one built
+    /// some other way might not
+    fn tag(&self, id: u32) -> Option<FieldPose> {
+        self.layout.tag(id).map(|tag| FieldPose {
+            position: tag.position(),
+            heading: tag.yaw,
+        })
+    }
+
+    fn mirror(&self, alliance: Alliance, pose: FieldPose) -> FieldPose {
+        AllianceFlip::with_layout(self.layout, alliance == Alliance::Red).mirror(pose)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector2;
+    use uom::si::angle::degree;
+
+    use super::{Alliance, Field};
+    use crate::{constants::field::SPEAKER_Y, field::layout::Layout};
+
+    #[test]
+    fn speaker_matches_constants() {
+        let layout = Layout::official();
+        let field = Field::new(&layout);
+
+        let speaker = field.speaker(Alliance::Blue).unwrap();
+        assert!((speaker.position.y - SPEAKER_Y).abs() < 0.01);
+        assert!(speaker.heading.get::<degree>().abs() < 1e-6);
+    }
+
+    #[test]
+    fn red_mirrors_blue() {
+        let layout = Layout::official();
+        let field = Field::new(&layout);
+        let mirrored = |blue: Vector2<f64>| Vector2::new(layout.length - blue.x, blue.y);
+
+        for (blue, red) in [
+            (field.speaker(Alliance::Blue), field.speaker(Alliance::Red)),
+            (field.amp(Alliance::Blue), field.amp(Alliance::Red)),
+            (field.source(Alliance::Blue), field.source(Alliance::Red)),
+        ] {
+            let (blue, red) = (blue.unwrap(), red.unwrap());
+            assert!((mirrored(blue.position) - red.position).norm() < 0.01);
+            let heading = (blue.heading + red.heading)
+                .get::<degree>()
+                .rem_euclid(360.);
+            assert!((heading - 180.).abs() < 0.1, "{heading}");
+        }
+
+        let blue = field.stage_center(Alliance::Blue);
+        assert!((mirrored(blue) - field.stage_center(Alliance::Red)).norm() < 0.01);
+
+        for (blue, red) in field
+            .spike_notes(Alliance::Blue)
+            .iter()
+            .zip(field.spike_notes(Alliance::Red))
+        {
+            assert!((mirrored(*blue) - red).norm() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn stage_is_where_its_tags_are() {
+        let layout = Layout::official();
+        let field = Field::new(&layout);
+
+        for (alliance, ids) in [
+            (Alliance::Blue, [14, 15, 16]),
+            (Alliance::Red, [11, 12, 13]),
+        ] {
+            assert!(field.under_stage(alliance, field.stage_center(alliance)));
+            let speaker = field.speaker(alliance).unwrap();
+            assert!(!field.under_stage(alliance, speaker.position));
+            assert!(!field.under_stage(alliance.opponent(), field.stage_center(alliance)));
+
+            // the tags hang on the inside of the legs
+            let tags = ids.map(|id| layout.tag(id).unwrap().position());
+            let centroid = tags.iter().sum::<Vector2<f64>>() / 3.;
+            assert!((centroid - field.stage_center(alliance)).norm() < 0.05);
+        }
+    }
+
+    #[test]
+    fn notes() {
+        let layout = Layout::official();
+        let field = Field::new(&layout);
+
+        let notes = field.centerline_notes();
+        assert_eq!(notes.len(), 5);
+        assert!(notes.iter().all(|note| note.x == layout.length / 2.));
+        assert_eq!(notes[2].y, field.spike_notes(Alliance::Blue)[2].y);
+    }
+}
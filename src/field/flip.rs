@@ -0,0 +1,278 @@
+use frcrs::alliance_station;
+use nalgebra::Vector2;
+use uom::si::{angle::degree, f64::Angle};
+
+use super::{Alliance, FieldPose, Layout, LAYOUT};
+use crate::{constants::RED_REFLECTION_METERS, telemetry::Pose};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symmetry {
+    /// red's half is blue's reflected across the center line, like 2024
+    Mirror,
+    /// red's half is blue's turned 180 degrees about the center
+    Rotational,
+}
+
+/// One point of a trajectory, in meters, meters per second and choreo's
+/// heading
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sample {
+    pub position: Vector2<f64>,
+    pub velocity: Vector2<f64>,
+    pub heading: Angle,
+}
+
+/// Turns anything drawn for blue into what it is for our alliance, and
+/// anything in the layout's field coordinates into our odometry's
+///
+/// On a mirrored field odometry measures x out from our own alliance wall,
+/// and on red puts the robot at `RED_REFLECTION_METERS - y`. So red's frame
+/// is blue's reflected side to side about y = `reflection / 2`, which is
+/// 2.06m and not the field's center line. Everything drawn for blue goes
+/// through the same reflection so it lines up with that odometry
+///
+/// On a rotational field odometry is field coordinates for both alliances,
+/// and anything drawn for blue is turned 180 degrees about the field's
+/// center. Either way flipping twice gets back where you started
+///
+/// Field coordinates (the apriltag layout, vision, `Field`) are blue's
+/// odometry as is. For red they're moved onto blue's half first, see
+/// `from_field`, this is the only place that happens
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AllianceFlip {
+    symmetry: Symmetry,
+    /// meters, red's y is this minus blue's on a mirrored field
+    reflection: f64,
+    /// meters, the field's, from the blue wall to the red one
+    length: f64,
+    /// meters, the field's, across the alliance walls
+    width: f64,
+    red: bool,
+}
+
+impl AllianceFlip {
+    /// for a field the size `layout` says it is
+    pub fn new(symmetry: Symmetry, layout: &Layout, red: bool) -> Self {
+        Self {
+            symmetry,
+            reflection: RED_REFLECTION_METERS,
+            length: layout.length,
+            width: layout.width,
+            red,
+        }
+    }
+
+    /// this year's field, for the alliance the driver station says we're on
+    pub fn current() -> Self {
+        Self::for_alliance(alliance_station().red())
+    }
+
+    pub fn for_alliance(red: bool) -> Self {
+        Self::with_layout(&LAYOUT, red)
+    }
+
+    /// this year's field is mirrored
+    pub fn with_layout(layout: &Layout, red: bool) -> Self {
+        Self::new(Symmetry::Mirror, layout, red)
+    }
+
+    pub fn red(&self) -> bool {
+        self.red
+    }
+
+    pub fn alliance(&self) -> Alliance {
+        Alliance::from_red(self.red)
+    }
+
+    /// true if anything actually moves
+    pub fn flips(&self) -> bool {
+        self.red
+    }
+
+    pub fn position(&self, position: Vector2<f64>) -> Vector2<f64> {
+        match (self.flips(), self.symmetry) {
+            (false, _) => position,
+            (true, Symmetry::Mirror) => Vector2::new(position.x, self.reflection - position.y),
+            (true, Symmetry::Rotational) => {
+                Vector2::new(self.length - position.x, self.width - position.y)
+            }
+        }
+    }
+
+    pub fn velocity(&self, velocity: Vector2<f64>) -> Vector2<f64> {
+        match (self.flips(), self.symmetry) {
+            (false, _) => velocity,
+            (true, Symmetry::Mirror) => Vector2::new(velocity.x, -velocity.y),
+            (true, Symmetry::Rotational) => -velocity,
+        }
+    }
+
+    /// either direction of rotation, mirroring negates it either way
+    pub fn heading(&self, heading: Angle) -> Angle {
+        match (self.flips(), self.symmetry) {
+            (false, _) => heading,
+            (true, Symmetry::Mirror) => -heading,
+            (true, Symmetry::Rotational) => heading + Angle::new::<degree>(180.),
+        }
+    }
+
+    pub fn sample(&self, sample: Sample) -> Sample {
+        Sample {
+            position: self.position(sample.position),
+            velocity: self.velocity(sample.velocity),
+            heading: self.heading(sample.heading),
+        }
+    }
+
+    /// Red's copy of something on blue's half, both in field coordinates
+    ///
+    /// Mirrored across the center line x is measured back from the red wall
+    /// and headings turn around, rotated about the center y is too
+    pub fn mirror(&self, pose: FieldPose) -> FieldPose {
+        if !self.red {
+            return pose;
+        }
+
+        let FieldPose { position, heading } = pose;
+        match self.symmetry {
+            Symmetry::Mirror => FieldPose {
+                position: Vector2::new(self.length - position.x, position.y),
+                heading: Angle::new::<degree>(180.) - heading,
+            },
+            Symmetry::Rotational => FieldPose {
+                position: Vector2::new(self.length - position.x, self.width - position.y),
+                heading: heading + Angle::new::<degree>(180.),
+            },
+        }
+    }
+
+    /// Where something in field coordinates is in our odometry
+    ///
+    /// Take it to blue's half, where red's odometry sees it the way blue's
+    /// does, then flip it like anything drawn for blue. On a rotational field
+    /// the two cancel out
+    pub fn from_field(&self, pose: FieldPose) -> FieldPose {
+        let mirrored = self.mirror(pose);
+        FieldPose {
+            position: self.position(mirrored.position),
+            heading: self.heading(mirrored.heading),
+        }
+    }
+
+    /// `theta` in degrees, like everywhere a `Pose` goes
+    pub fn pose(&self, pose: &Pose) -> Pose {
+        let position = self.position(Vector2::new(pose.x, pose.y));
+        let theta = self.heading(Angle::new::<degree>(pose.theta));
+
+        Pose {
+            x: position.x,
+            y: position.y,
+            theta: theta.get::<degree>(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector2;
+    use uom::si::{angle::degree, f64::Angle};
+
+    use super::{AllianceFlip, Sample, Symmetry};
+    use crate::{
+        constants::RED_REFLECTION_METERS,
+        field::{Alliance, Field, Layout},
+        telemetry::Pose,
+    };
+
+    fn sample() -> Sample {
+        Sample {
+            position: Vector2::new(0.469, 7.034497),
+            velocity: Vector2::new(1.5, -0.25),
+            heading: Angle::new::<degree>(30.),
+        }
+    }
+
+    #[test]
+    fn blue_is_as_drawn() {
+        let flip = AllianceFlip::for_alliance(false);
+        assert!(!flip.flips());
+        assert_eq!(flip.sample(sample()), sample());
+    }
+
+    #[test]
+    fn red_mirrors() {
+        let flip = AllianceFlip::for_alliance(true);
+        let flipped = flip.sample(sample());
+
+        assert_eq!(flipped.position.x, 0.469);
+        assert_eq!(flipped.position.y, RED_REFLECTION_METERS - 7.034497);
+        assert_eq!(flipped.velocity, Vector2::new(1.5, 0.25));
+        assert_eq!(flipped.heading, -sample().heading);
+    }
+
+    /// degrees apart, either way around
+    fn turned(a: Angle, b: Angle) -> f64 {
+        let apart = (a - b).get::<degree>().rem_euclid(360.);
+        apart.min(360. - apart)
+    }
+
+    #[test]
+    fn round_trips() {
+        let layout = Layout::official();
+        for symmetry in [Symmetry::Mirror, Symmetry::Rotational] {
+            for red in [false, true] {
+                let flip = AllianceFlip::new(symmetry, &layout, red);
+                let there_and_back = flip.sample(flip.sample(sample()));
+
+                assert!((there_and_back.position - sample().position).norm() < 1e-12);
+                assert_eq!(there_and_back.velocity, sample().velocity);
+                assert!(turned(there_and_back.heading, sample().heading) < 1e-9);
+
+                let pose = Pose {
+                    x: 1.,
+                    y: 2.,
+                    theta: 45.,
+                };
+                let back = flip.pose(&flip.pose(&pose));
+                assert!((back.x - pose.x).abs() < 1e-12);
+                assert!((back.y - pose.y).abs() < 1e-12);
+                let theta = Angle::new::<degree>(back.theta - pose.theta);
+                assert!(turned(theta, Angle::new::<degree>(0.)) < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn rotational_turns_about_the_center() {
+        let layout = Layout::official();
+        let flip = AllianceFlip::new(Symmetry::Rotational, &layout, true);
+        let flipped = flip.sample(sample());
+
+        let center = Vector2::new(layout.length, layout.width) / 2.;
+        assert!((flipped.position - center + (sample().position - center)).norm() < 1e-12);
+        assert_eq!(flipped.velocity, -sample().velocity);
+        assert!(turned(flipped.heading, sample().heading) > 180. - 1e-9);
+
+        // odometry is field coordinates already
+        let speaker = Field::new(&layout).speaker(Alliance::Red).unwrap();
+        let ours = flip.from_field(speaker);
+        assert!((ours.position - speaker.position).norm() < 1e-12);
+        assert!(turned(ours.heading, speaker.heading) < 1e-9);
+    }
+
+    #[test]
+    fn field_to_odometry() {
+        let layout = Layout::official();
+        let field = Field::new(&layout);
+
+        let blue = AllianceFlip::with_layout(&layout, false);
+        let speaker = field.speaker(Alliance::Blue).unwrap();
+        assert_eq!(blue.from_field(speaker), speaker);
+
+        // red's speaker is where blue's would be, once it's been reflected
+        let red = AllianceFlip::with_layout(&layout, true);
+        let ours = red.from_field(field.speaker(Alliance::Red).unwrap());
+        assert!((ours.position - red.position(speaker.position)).norm() < 0.01);
+        assert!(turned(ours.heading, red.heading(speaker.heading)) < 0.1);
+    }
+}
@@ -0,0 +1,209 @@
+use std::fs;
+use std::ops::RangeInclusive;
+
+use lazy_static::lazy_static;
+use nalgebra::{Vector2, Vector3};
+use serde::Deserialize;
+use uom::si::{angle::radian, f64::Angle};
+
+/// the official layout, built in
+const CRESCENDO: &str = include_str!("2024-crescendo.json");
+/// the ids on this year's field
+const TAGS: RangeInclusive<u32> = 1..=16;
+
+lazy_static! {
+    pub static ref LAYOUT: Layout = Layout::load();
+}
+
+/// Where every AprilTag is, in WPILib's field coordinates: meters, the blue
+/// alliance wall at x = 0, counterclockwise headings
+#[derive(Clone, Debug)]
+pub struct Layout {
+    pub tags: Vec<AprilTag>,
+    /// meters
+    pub length: f64,
+    pub width: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AprilTag {
+    pub id: u32,
+    pub translation: Vector3<f64>,
+    /// the way the tag faces, around z
+    pub yaw: Angle,
+}
+
+impl AprilTag {
+    pub fn position(&self) -> Vector2<f64> {
+        self.translation.xy()
+    }
+}
+
+// the layout json as WPILib writes it
+#[derive(Deserialize)]
+struct LayoutFile {
+    tags: Vec<TagFile>,
+    field: FieldFile,
+}
+
+#[derive(Deserialize)]
+struct FieldFile {
+    length: f64,
+    width: f64,
+}
+
+#[derive(Deserialize)]
+struct TagFile {
+    #[serde(rename = "ID")]
+    id: u32,
+    pose: PoseFile,
+}
+
+#[derive(Deserialize)]
+struct PoseFile {
+    translation: TranslationFile,
+    rotation: RotationFile,
+}
+
+#[derive(Deserialize)]
+struct TranslationFile {
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+#[derive(Deserialize)]
+struct RotationFile {
+    quaternion: QuaternionFile,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+struct QuaternionFile {
+    w: f64,
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+impl QuaternionFile {
+    fn yaw(&self) -> Angle {
+        let QuaternionFile { w, x, y, z } = *self;
+        Angle::new::<radian>((2. * (w * z + x * y)).atan2(1. - 2. * (y * y + z * z)))
+    }
+}
+
+impl Layout {
+    /// deployed next to the trajectories, if the field's been measured
+    const PATH: &'static str = "/home/lvuser/deploy/apriltags.json";
+
+    /// load from the rio, falling back to the official layout if there
+    /// isn't one or it's missing any of this year's tags
+    pub fn load() -> Self {
+        let Ok(buf) = fs::read_to_string(Self::PATH) else {
+            return Self::official();
+        };
+
+        Self::parse(&buf)
+            .map_err(|e| e.to_string())
+            .and_then(|layout| layout.complete().map(|()| layout))
+            .unwrap_or_else(|e| {
+                println!("ignoring {}: {e}", Self::PATH);
+                Self::official()
+            })
+    }
+
+    pub fn official() -> Self {
+        Self::parse(CRESCENDO).expect("built in apriltag layout is valid")
+    }
+
+    pub fn parse(buf: &str) -> serde_json::Result<Self> {
+        let file: LayoutFile = serde_json::from_str(buf)?;
+
+        let tags = file
+            .tags
+            .iter()
+            .map(|tag| {
+                let TranslationFile { x, y, z } = tag.pose.translation;
+                AprilTag {
+                    id: tag.id,
+                    translation: Vector3::new(x, y, z),
+                    yaw: tag.pose.rotation.quaternion.yaw(),
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            tags,
+            length: file.field.length,
+            width: file.field.width,
+        })
+    }
+
+    pub fn tag(&self, id: u32) -> Option<&AprilTag> {
+        self.tags.iter().find(|tag| tag.id == id)
+    }
+
+    /// every one of `TAGS` is there
+    fn complete(&self) -> Result<(), String> {
+        let missing: Vec<u32> = TAGS.filter(|id| self.tag(*id).is_none()).collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("missing tags {missing:?}"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uom::si::angle::degree;
+
+    use super::Layout;
+
+    #[test]
+    fn official_parses() {
+        let layout = Layout::official();
+        assert_eq!(layout.tags.len(), 16);
+        assert!((layout.length - 16.541).abs() < 1e-3);
+        assert!((layout.width - 8.211).abs() < 1e-3);
+        assert!(layout.tag(17).is_none());
+
+        let speaker = layout.tag(4).unwrap();
+        assert!((speaker.yaw.get::<degree>().abs() - 180.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn incomplete_layouts_are_caught() {
+        let mut layout = Layout::official();
+        assert!(layout.complete().is_ok());
+
+        layout.tags.retain(|tag| tag.id != 7);
+        assert_eq!(layout.complete(), Err("missing tags [7]".to_owned()));
+    }
+
+    #[test]
+    fn tags_mirror_across_center() {
+        let layout = Layout::official();
+        let pairs = [
+            (1, 10),
+            (2, 9),
+            (3, 8),
+            (4, 7),
+            (5, 6),
+            (11, 16),
+            (12, 15),
+            (13, 14),
+        ];
+
+        for (red, blue) in pairs {
+            let red = layout.tag(red).unwrap();
+            let blue = layout.tag(blue).unwrap();
+
+            assert!((red.translation.x - (layout.length - blue.translation.x)).abs() < 0.01);
+            assert!((red.translation.y - blue.translation.y).abs() < 0.01);
+            let heading = (red.yaw + blue.yaw).get::<degree>().rem_euclid(360.);
+            assert!((heading - 180.).abs() < 0.1, "{} {heading}", red.id);
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod elements;
+pub mod flip;
+pub mod layout;
+
+pub use elements::*;
+pub use flip::*;
+pub use layout::{AprilTag, Layout, LAYOUT};
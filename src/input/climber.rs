@@ -1,17 +1,39 @@
-use crate::subsystems::Climber;
+use std::time::Duration;
+
+use uom::si::f64::Angle;
+
+use crate::{subsystems::Climber, telemetry};
 
 use super::{Controllers, GamepadState};
 
-pub async fn control_climber(climber: &mut Climber, controllers: &mut Controllers) {
+pub async fn control_climber(
+    climber: &mut Climber,
+    controllers: &mut Controllers,
+    roll: Option<Angle>,
+    dt: &Duration,
+) {
     let operator = &mut controllers.operator;
     let _right_drive = &mut controllers.right_drive;
     let left_drive = &mut controllers.left_drive;
     let gamepad = &mut controllers.gamepad;
     let gamepad_state = &mut controllers.gamepad_state;
 
+    let (left, right) = climber.extension();
+    telemetry::put_number("climber left", left.unwrap_or(f64::NAN)).await;
+    telemetry::put_number("climber right", right.unwrap_or(f64::NAN)).await;
+    telemetry::put_bool("climber homed", climber.homed()).await;
+    telemetry::put_bool("climber stalled", climber.stalled()).await;
+
     let mut climbing = false;
     if left_drive.get(3) {
-        climber.set(1.);
+        if let Some(roll) = roll {
+            climber.climb_level(roll);
+        } else {
+            climber.set(1.);
+        }
+        climbing = true;
+    } else if matches!(gamepad_state, GamepadState::Climb) && gamepad.right_stick() {
+        climber.raise(dt);
         climbing = true;
     } else {
         if matches!(gamepad_state, GamepadState::Climb) && gamepad.left_trigger() > 0. {
@@ -42,6 +64,6 @@ pub async fn control_climber(climber: &mut Climber, controllers: &mut Controller
         }
     };
     if !climbing {
-        climber.stop();
+        climber.set(0.);
     }
 }
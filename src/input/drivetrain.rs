@@ -1,12 +1,16 @@
-use frcrs::{alliance_station, deadzone};
-use nalgebra::ComplexField;
+use std::time::Duration;
+
+use frcrs::deadzone;
+use nalgebra::{ComplexField, Vector2};
 use uom::si::{
     angle::{degree, radian},
     f64::Angle,
 };
 
 use crate::{
+    aim::heading::{heading_to, wrap, HeadingController},
     constants::drivetrain::{PODIUM_SHOT_ANGLE, SWERVE_TURN_KP},
+    field::AllianceFlip,
     subsystems::Drivetrain,
     telemetry,
 };
@@ -16,12 +20,18 @@ use super::{Controllers, GamepadState};
 #[derive(Default)]
 pub struct DrivetrainControlState {
     saved_angle: Option<Angle>,
+    aim: HeadingController,
+    /// where to point the shooter, leading the speaker while moving
+    pub aim_target: Option<Vector2<f64>>,
+    /// how far off the speaker we are, while aiming
+    pub aim_error: Option<Angle>,
 }
 
 pub async fn control_drivetrain(
     drivetrain: &mut Drivetrain,
     controllers: &mut Controllers,
     state: &mut DrivetrainControlState,
+    dt: &Duration,
 ) {
     let right_drive = &mut controllers.right_drive;
     let left_drive = &mut controllers.left_drive;
@@ -58,12 +68,21 @@ pub async fn control_drivetrain(
         *saved_angle = Some(drivetrain.get_angle());
     }
 
-    let rot = if right_drive.get(2) {
-        let mut error = drivetrain.get_offset() + Angle::new::<degree>(PODIUM_SHOT_ANGLE);
-        if alliance_station().blue() {
-            error *= -1.;
-        }
-        -error.get::<radian>() * SWERVE_TURN_KP
+    // right 4 spins up for the shot, aim separately so the driver can line up first
+    let aiming = left_drive.get(5) && state.aim_target.is_some();
+    if !aiming {
+        state.aim.reset();
+        state.aim_error = None;
+    }
+
+    let rot = if aiming {
+        let target = state.aim_target.unwrap_or_default();
+        let goal = heading_to(drivetrain.odometry.position, target);
+        let measured = drivetrain.get_offset();
+        state.aim_error = Some(wrap(goal - measured));
+        state.aim.calculate(measured, goal, dt)
+    } else if right_drive.get(2) {
+        podium_turn(drivetrain.get_offset(), &AllianceFlip::current())
     } else if hold_angle {
         if let Some(ref saved_angle) = (saved_angle).as_ref() {
             let error = drivetrain.get_angle() - **saved_angle;
@@ -96,4 +115,46 @@ pub async fn control_drivetrain(
     telemetry::put_number("Odo Y", drivetrain.odometry.position.y).await;
 
     telemetry::put_number("Angle", angle.get::<degree>()).await;
+    if let Some(aim_error) = state.aim_error {
+        telemetry::put_number("aim error", aim_error.get::<degree>()).await;
+    }
+}
+
+/// Turn towards the podium shot, drawn for blue and mirrored for red
+///
+/// `offset` is the heading from the driver's zero, the result is rotation
+/// to drive with
+fn podium_turn(offset: Angle, flip: &AllianceFlip) -> f64 {
+    let goal = flip.heading(Angle::new::<degree>(PODIUM_SHOT_ANGLE));
+    let error = offset - goal;
+    -error.get::<radian>() * SWERVE_TURN_KP
+}
+
+#[cfg(test)]
+mod tests {
+    use uom::si::{
+        angle::{degree, radian},
+        f64::Angle,
+    };
+
+    use super::podium_turn;
+    use crate::{constants::drivetrain::PODIUM_SHOT_ANGLE, field::AllianceFlip};
+
+    #[test]
+    fn podium_aim_settles_on_both_alliances() {
+        for red in [false, true] {
+            let flip = AllianceFlip::for_alliance(red);
+            let mut offset = Angle::new::<degree>(0.);
+
+            // full rotation as 10 radians per second, for two seconds at 50Hz
+            for _ in 0..100 {
+                let turn = podium_turn(offset, &flip);
+                offset += Angle::new::<radian>(turn * 10. * 0.02);
+            }
+
+            let goal = PODIUM_SHOT_ANGLE * if red { -1. } else { 1. };
+            let error = offset.get::<degree>() - goal;
+            assert!(error.abs() < 1., "red {red}: {error} degrees off");
+        }
+    }
 }
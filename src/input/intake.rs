@@ -4,7 +4,7 @@ use uom::si::{angle::degree, f64::Angle};
 
 use crate::{
     constants::intake::{INTAKE_DOWN_GOAL, INTAKE_UP_GOAL},
-    subsystems::Intake,
+    subsystems::{Intake, IntakeState},
     telemetry,
 };
 
@@ -16,11 +16,24 @@ pub async fn control_intake(intake: &mut Intake, controllers: &mut Controllers,
     let gamepad = &mut controllers.gamepad;
     let gamepad_state = &mut controllers.gamepad_state;
     telemetry::put_bool("intake at limit {}", intake.at_limit()).await;
+    telemetry::put_bool("intake homed", intake.homed()).await;
+    telemetry::put_bool(
+        "intake homing failed",
+        intake.state() == IntakeState::HomingFailed,
+    )
+    .await;
+    // alarm, someone tried to move the intake before it knew where it was
+    telemetry::put_bool("intake unhomed move refused", intake.refused()).await;
     telemetry::put_number(
         "intake position {}",
         intake.actuate_position().get::<degree>(),
     )
     .await;
+    if let Some(setpoint) = intake.actuate_setpoint() {
+        let error = setpoint - intake.actuate_position();
+        telemetry::put_number("intake setpoint", setpoint.get::<degree>()).await;
+        telemetry::put_number("intake setpoint error", error.get::<degree>()).await;
+    }
 
     if matches!(gamepad_state, GamepadState::Manual | GamepadState::Auto)
         && gamepad.left_trigger() > 0.
@@ -54,12 +67,12 @@ pub async fn control_intake(intake: &mut Intake, controllers: &mut Controllers,
         }
     } else {
         if operator.get(3) || matches!(gamepad_state, GamepadState::Auto) && gamepad.right_stick() {
-            intake.actuate_to_trapezoid(Angle::new::<degree>(INTAKE_UP_GOAL), &dt);
+            intake.actuate_profiled(Angle::new::<degree>(INTAKE_UP_GOAL), &dt);
             //intake.actuate_to(Angle::new::<degree>(INTAKE_UP_GOAL));
         } else if operator.get(4)
             || matches!(gamepad_state, GamepadState::Auto) && gamepad.left_stick()
         {
-            intake.actuate_to_trapezoid(Angle::new::<degree>(INTAKE_DOWN_GOAL), &dt);
+            intake.actuate_profiled(Angle::new::<degree>(INTAKE_DOWN_GOAL), &dt);
             //intake.actuate_to(Angle::new::<degree>(INTAKE_DOWN_GOAL));
         }
     }
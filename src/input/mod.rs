@@ -1,5 +1,6 @@
 use std::{
-    borrow::BorrowMut, cell::RefCell, ops::Deref, rc::Rc, sync::atomic::AtomicBool, time::Duration,
+    borrow::BorrowMut, cell::{Cell, RefCell}, mem, ops::Deref, rc::Rc, sync::atomic::AtomicBool,
+    time::{Duration, Instant},
 };
 
 use frcrs::{
@@ -8,13 +9,14 @@ use frcrs::{
 };
 
 use tokio::{
-    task::{JoinHandle, LocalSet},
+    task::{spawn_local, JoinHandle, LocalSet},
     time::sleep,
 };
 use uom::si::{angle::degree, f64::Angle};
 
 use crate::{
-    auto::{lower_intake, raise_intake}, constants::intake::{INTAKE_DOWN_GOAL, INTAKE_DOWN_THRESHOLD, INTAKE_UP_GOAL, INTAKE_UP_THRESHOLD}, subsystems::{wait, Climber, Drivetrain, Intake, Shooter}, telemetry::{self, TelemetryStore, TELEMETRY}
+    aim::{moving::solve_with_map, shot_map::ShotMap, speaker},
+    auto::{chosen_auto, lower_intake, publish_readiness, raise_intake, run_auto}, constants::{intake::{INTAKE_DOWN_GOAL, INTAKE_GRAB_TIMEOUT, INTAKE_SPIN_UP_TIMEOUT, INTAKE_UP_GOAL}, jam::{EJECT_TIME, JAM_RETRIES, RESEAT_TIME, REVERSE_TIME}, shooter::HANDOFF_TIMEOUT, WATCHDOG_RECOVERY}, crash, subsystems::{describe, neutral_all, wait, wait_for, Alarm, Climber, Distribution, Drivetrain, Health, Intake, Interrupted, JamDetector, NoteReadings, NoteSensor, Outcome, Pivot, Power, Sequence, Severity, Shooter}, mode::{Lifecycle, Mode, Phase}, scheduler::{Scheduler, Task}, telemetry::{self, TelemetryStore, TELEMETRY}, watchdog::Watchdog, wrapper
 };
 
 use self::{
@@ -33,11 +35,22 @@ mod shooter;
 pub struct Ferris {
     pub drivetrain: Rc<RefCell<Drivetrain>>,
     pub intake: Rc<RefCell<Intake>>,
+    /// the intake's actuators, which `intake` shares
+    pivot: Rc<RefCell<Pivot>>,
     pub shooter: Rc<RefCell<Shooter>>,
     pub climber: Rc<RefCell<Climber>>,
+    pub note: Rc<RefCell<NoteSensor>>,
+    pub health: Rc<RefCell<Health>>,
+    pub power: Rc<RefCell<Power>>,
+    pub distribution: Rc<RefCell<Distribution>>,
+    auto: Rc<RefCell<Option<JoinHandle<()>>>>,
     grab: Rc<RefCell<Option<JoinHandle<()>>>>,
     stage: Rc<RefCell<Option<JoinHandle<()>>>>,
     grab_full: Rc<RefCell<Option<JoinHandle<()>>>>,
+    score_amp: Rc<RefCell<Option<JoinHandle<()>>>>,
+    unjam: Rc<RefCell<Option<JoinHandle<()>>>>,
+    /// `stop_all` couldn't reset everything yet
+    stopping: Rc<Cell<bool>>,
     shooter_state: Rc<RefCell<(bool, bool)>>,
     teleop_state: Rc<RefCell<TeleopState>>,
     pub telemetry: TelemetryStore,
@@ -47,6 +60,7 @@ pub struct Ferris {
 struct TeleopState {
     drivetrain_state: DrivetrainControlState,
     shooter_state: ShooterControlState,
+    shot_map: ShotMap,
 }
 
 pub struct Controllers {
@@ -68,7 +82,9 @@ pub enum GamepadState {
 impl Ferris {
     pub fn new() -> Self {
         let drivetrain = Rc::new(RefCell::new(Drivetrain::new()));
-        let intake = Rc::new(RefCell::new(Intake::new()));
+        let intake = Intake::new();
+        let pivot = intake.pivot();
+        let intake = Rc::new(RefCell::new(intake));
         let shooter = Rc::new(RefCell::new(Shooter::new()));
         let climber = Rc::new(RefCell::new(Climber::new()));
         let shooter_state = Rc::new(RefCell::new((false, false)));
@@ -77,16 +93,150 @@ impl Ferris {
         Self {
             drivetrain,
             intake,
+            pivot,
             shooter,
             climber,
+            note: Rc::new(RefCell::new(NoteSensor::default())),
+            health: Rc::new(RefCell::new(Health::default())),
+            power: Rc::new(RefCell::new(Power::default())),
+            distribution: Rc::new(RefCell::new(Distribution::new())),
+            auto: Rc::new(RefCell::new(None)),
             grab: Rc::new(RefCell::new(None)),
             grab_full: Rc::new(RefCell::new(None)),
             shooter_state,
             stage: Rc::new(RefCell::new(None)),
-            teleop_state: Rc::new(RefCell::new(Default::default())),
+            score_amp: Rc::new(RefCell::new(None)),
+            unjam: Rc::new(RefCell::new(None)),
+            stopping: Rc::new(Cell::new(false)),
+            teleop_state: Rc::new(RefCell::new(TeleopState {
+                shot_map: ShotMap::load(),
+                ..Default::default()
+            })),
             telemetry,
         }
     }
+
+    /// Abort every spawned sequence and stop every motor, so nothing keeps
+    /// its last setpoint into the next mode
+    ///
+    /// An aborted task isn't dropped until the `LocalSet` next runs, so
+    /// whatever it borrowed is still borrowed here. The motors are stopped
+    /// without borrowing anything, and the subsystems are reset by
+    /// `finish_stop` once they're free
+    pub fn stop_all(&self) {
+        self.stop_sequences();
+        if let Some(auto) = self.auto.take() {
+            auto.abort();
+        }
+
+        neutral_all();
+        self.stopping.set(true);
+        self.finish_stop();
+        self.reset_teleop();
+    }
+
+    /// clear every subsystem's setpoints, every loop until it's done
+    fn finish_stop(&self) {
+        if !self.stopping.get() {
+            return;
+        }
+
+        let drivetrain = self.drivetrain.try_borrow();
+        let intake = self.intake.try_borrow_mut();
+        let shooter = self.shooter.try_borrow_mut();
+        let climber = self.climber.try_borrow_mut();
+        let (Ok(drivetrain), Ok(mut intake), Ok(mut shooter), Ok(mut climber)) =
+            (drivetrain, intake, shooter, climber)
+        else {
+            return;
+        };
+
+        drivetrain.stop();
+        intake.stop_rollers();
+        intake.stop_actuate();
+        shooter.stop();
+        climber.reset();
+        self.stopping.set(false);
+    }
+
+    /// abort anything teleop spawned
+    fn stop_sequences(&self) {
+        for sequence in [
+            &self.grab,
+            &self.stage,
+            &self.grab_full,
+            &self.score_amp,
+            &self.unjam,
+        ] {
+            if let Some(sequence) = sequence.take() {
+                sequence.abort();
+            }
+        }
+    }
+
+    /// forget saved headings, toggles and latched flywheels, keeping the shot map
+    fn reset_teleop(&self) {
+        let Ok(mut state) = self.teleop_state.try_borrow_mut() else {
+            return;
+        };
+        let shot_map = mem::take(&mut state.shot_map);
+        *state = TeleopState {
+            shot_map,
+            ..Default::default()
+        };
+    }
+
+    /// What each subsystem does as the robot changes modes
+    pub fn lifecycle(&self) -> Lifecycle<Ferris> {
+        let mut lifecycle = Lifecycle::new(Ferris::stop_all);
+        lifecycle.always(Ferris::finish_stop);
+
+        lifecycle.on(Mode::Auto, Phase::Init, "auto", |robot: &Ferris| {
+            let robot_ = robot.clone();
+            robot.auto.replace(Some(spawn_local(async move {
+                let chosen = chosen_auto(&robot_).await;
+                run_auto(chosen, robot_).await;
+            })));
+        });
+        lifecycle.on(Mode::Auto, Phase::Exit, "auto", |robot: &Ferris| {
+            if let Some(auto) = robot.auto.take() {
+                auto.abort();
+            }
+        });
+        // a routine that finishes early, or panics, would leave the drivetrain
+        // and flywheels at their last output until teleop
+        lifecycle.stop_when(Mode::Auto, "auto", |robot: &Ferris| {
+            robot
+                .auto
+                .try_borrow()
+                .is_ok_and(|auto| auto.as_ref().is_some_and(JoinHandle::is_finished))
+        });
+
+        lifecycle.on(Mode::Teleop, Phase::Init, "teleop", Ferris::reset_teleop);
+        lifecycle.on(Mode::Teleop, Phase::Exit, "teleop", Ferris::stop_sequences);
+
+        // homing drives the bar into its stop, which can't happen while disabled
+        for mode in [Mode::Auto, Mode::Teleop, Mode::Test] {
+            lifecycle.on(mode, Phase::Periodic, "amp bar", |robot: &Ferris| {
+                // `score_amp` updates it itself while it holds the shooter
+                if let Ok(mut shooter) = robot.shooter.try_borrow_mut() {
+                    shooter.update_amp(&Task::Control.period());
+                }
+            });
+            lifecycle.on(mode, Phase::Periodic, "intake actuate", |robot: &Ferris| {
+                // even while a sequence holds the intake
+                if let Ok(mut pivot) = robot.pivot.try_borrow_mut() {
+                    pivot.hold();
+                }
+            });
+        }
+
+        lifecycle.on(Mode::Test, Phase::Periodic, "drivetrain", |robot: &Ferris| {
+            robot.drivetrain.deref().borrow_mut().write_absolute();
+        });
+
+        lifecycle
+    }
 }
 
 pub async fn container<'a>(
@@ -94,28 +244,53 @@ pub async fn container<'a>(
     robot: &'a Ferris,
     executor: &'a LocalSet,
     dt: Duration,
+    scheduler: &mut Scheduler,
 ) {
     let TeleopState {
         ref mut drivetrain_state,
         ref mut shooter_state,
+        ref shot_map,
     } = *robot.teleop_state.deref().borrow_mut();
 
+    scheduler.begin_section("drivetrain");
     if let Ok(mut drivetrain) = robot.drivetrain.try_borrow_mut() {
-        control_drivetrain(&mut drivetrain, controllers, drivetrain_state).await;
+        drivetrain.odometry.update_from_vision(robot.telemetry.clone()).await;
+        let odometry = &drivetrain.odometry;
+        let shot = solve_with_map(odometry.position, odometry.velocity, speaker(), shot_map);
+        drivetrain_state.aim_target = Some(shot.target);
+        shooter_state.shot = shot_map.get(shot.distance);
+
+        control_drivetrain(&mut drivetrain, controllers, drivetrain_state, &dt).await;
     } else {
+        drivetrain_state.aim_target = None;
+        shooter_state.shot = None;
     }
+    scheduler.end_section("drivetrain");
 
+    scheduler.begin_section("intake");
     if let Ok(mut intake) = robot.intake.try_borrow_mut() {
         control_intake(&mut intake, controllers, &dt).await;
     }
+    scheduler.end_section("intake");
 
+    scheduler.begin_section("shooter");
+    shooter_state.aim_error = drivetrain_state.aim_error;
+    shooter_state.shedding = robot.power.deref().borrow().shedding();
     if let Ok(mut shooter) = robot.shooter.try_borrow_mut() {
         control_shooter(&mut shooter, controllers, shooter_state).await;
     }
+    scheduler.end_section("shooter");
+
+    scheduler.begin_section("health");
+    update_health(robot).await;
+    scheduler.end_section("health");
 
+    scheduler.begin_section("climber");
+    let roll = robot.drivetrain.try_borrow().ok().and_then(|d| d.get_roll());
     if let Ok(mut climber) = robot.climber.try_borrow_mut() {
-        control_climber(&mut climber, controllers).await;
+        control_climber(&mut climber, controllers, roll, &dt).await;
     }
+    scheduler.end_section("climber");
 
     let red = alliance_station().red();
     telemetry::put_bool("red", red).await;
@@ -125,7 +300,7 @@ pub async fn container<'a>(
 
     let Controllers {
         left_drive: _,
-        right_drive: _,
+        ref mut right_drive,
         ref mut operator,
         ref mut gamepad,
         ref mut gamepad_state,
@@ -146,7 +321,7 @@ pub async fn container<'a>(
     {
         let intake = robot.intake.clone();
         robot.grab.replace(Some(executor.spawn_local(async move {
-            intake.deref().borrow_mut().grab().await;
+            let _ = intake.deref().borrow_mut().grab().await;
         })));
     } else if !operator.get(8) || *firing {
         if let Some(grab) = robot.grab.take() {
@@ -199,7 +374,7 @@ pub async fn container<'a>(
             let shooter = shooter.deref().try_borrow();
 
             if let (Ok(mut intake), Ok(shooter)) = (intake, shooter) {
-                stage(&mut intake, &shooter).await;
+                let _ = stage(&mut intake, &shooter).await;
             }
         })));
     } else if (!operator.get(7) && !matches!(gamepad_state, GamepadState::Auto))
@@ -211,6 +386,45 @@ pub async fn container<'a>(
         }
     }
 
+    if right_drive.get(5) && robot.score_amp.deref().try_borrow().is_ok_and(|n| n.is_none()) {
+        let shooter = robot.shooter.clone();
+        robot.score_amp.replace(Some(executor.spawn_local(async move {
+            if let Ok(mut shooter) = shooter.deref().try_borrow_mut() {
+                score_amp(&mut shooter).await;
+            }
+        })));
+    } else if !right_drive.get(5) {
+        if let Some(score_amp) = robot.score_amp.take() {
+            score_amp.abort();
+        }
+    }
+
+    if right_drive.get(6) && robot.unjam.deref().try_borrow().is_ok_and(|n| n.is_none()) {
+        let intake = robot.intake.clone();
+        let shooter = robot.shooter.clone();
+        robot.unjam.replace(Some(executor.spawn_local(async move {
+            let intake = intake.deref().try_borrow_mut();
+            let shooter = shooter.deref().try_borrow();
+
+            if let (Ok(mut intake), Ok(shooter)) = (intake, shooter) {
+                let _ = unjam(&mut intake, &shooter).await;
+            }
+        })));
+    } else if !right_drive.get(6) {
+        if let Some(unjam) = robot.unjam.take() {
+            unjam.abort();
+        }
+    }
+
+    if matches!(gamepad_state, GamepadState::Climb) && gamepad.b() {
+        let climber = robot.climber.clone();
+        executor.spawn_local(async move {
+            if let Ok(mut climber) = climber.try_borrow_mut() {
+                climber.home().await;
+            }
+        });
+    }
+
     if operator.get(9) || (matches!(gamepad_state, GamepadState::Climb) && gamepad.a()) {
         let intake = robot.intake.clone();
         executor.spawn_local(async move {
@@ -223,31 +437,299 @@ pub async fn container<'a>(
     //println!("doo dad: {}", get_dio(INTAKE_LIMIT));
 }
 
+/// Read the battery and scale the drivetrain to match, every loop in every mode
+pub async fn manage_power(robot: &Ferris, enabled: bool, auto: bool) {
+    let mut distribution = robot.distribution.deref().borrow_mut();
+    distribution.set_enabled(enabled, auto);
+    if distribution.sample() {
+        publish_distribution(&distribution).await;
+    }
+
+    let mut power = robot.power.deref().borrow_mut();
+    // a failed read keeps the last state rather than looking like a dead battery
+    if let Some(voltage) = wrapper::battery_voltage() {
+        power.update(voltage, wrapper::browned_out());
+    }
+
+    // a running auto holds the drivetrain, it picks the scale up next loop
+    if let Ok(mut drivetrain) = robot.drivetrain.try_borrow_mut() {
+        drivetrain.set_power_scale(power.drive_scale());
+    }
+
+    telemetry::put_number("battery voltage", power.voltage()).await;
+    telemetry::put_text("power state", power.state().name()).await;
+    telemetry::put_number("drive scale", power.drive_scale()).await;
+}
+
+async fn publish_distribution(distribution: &Distribution) {
+    if let Some(temperature) = distribution.temperature() {
+        telemetry::put_number("pdh temperature", temperature).await;
+    }
+    if let Some(current) = distribution.total_current() {
+        telemetry::put_number("pdh total current", current).await;
+    }
+    if let Some(energy) = distribution.total_energy() {
+        telemetry::put_number("match energy", energy).await;
+    }
+
+    let log = distribution.log();
+    for (subsystem, usage) in &log.subsystems {
+        telemetry::put_number(&format!("{subsystem} energy"), usage.energy).await;
+        telemetry::put_number(&format!("{subsystem} peak current"), usage.peak_current).await;
+    }
+    if let Ok(log) = serde_json::to_string(log) {
+        telemetry::put_text("power usage", &log).await;
+    }
+}
+
+/// The watchdog already stopped the motors, this just makes sure someone
+/// sees it, until the loop has run steadily for `WATCHDOG_RECOVERY`
+pub fn report_stalls(robot: &Ferris, watchdog: &Watchdog) {
+    let recovered = watchdog
+        .recovered_for()
+        .is_some_and(|recovered| recovered.as_secs_f64() > WATCHDOG_RECOVERY);
+
+    let alarms = match watchdog.take_stalls().last() {
+        Some(stall) => {
+            let device = format!("control loop ({})", stall.task);
+            let alarm = Alarm::new(&device, "stalled", Severity::Critical);
+            vec![alarm.with_value(stall.after * 1000.)]
+        }
+        None if recovered => vec![],
+        None => return,
+    };
+    if let Ok(mut health) = robot.health.try_borrow_mut() {
+        health.report("watchdog", alarms);
+    }
+}
+
+/// panics a spawned task or the telemetry server lived through, their crash
+/// reports have the details
+pub fn report_panics(robot: &Ferris) {
+    let Some(alarms) = crash::caught_panics() else {
+        return;
+    };
+    if let Ok(mut health) = robot.health.try_borrow_mut() {
+        health.report("panics", alarms);
+    }
+}
+
+/// where the note is, from whatever sensors aren't held by a sequence, every
+/// loop in every mode
+pub async fn update_note(robot: &Ferris) {
+    // whatever a running sequence is holding keeps its last reading
+    let readings = NoteReadings {
+        intake_stalled: robot.intake.try_borrow_mut().ok().map(|mut i| i.stalled()),
+        cam_limit: robot.intake.try_borrow().ok().map(|i| i.cam_limit()),
+        beam_break: robot.shooter.try_borrow().ok().map(|s| s.contains_note()),
+    };
+    if let Ok(mut note) = robot.note.try_borrow_mut() {
+        note.update(readings);
+        telemetry::put_text("note location", note.location().name()).await;
+        telemetry::put_number("note confidence", note.confidence()).await;
+        telemetry::put_bool("note staged", note.staged()).await;
+        telemetry::put_bool("note sensors disagree", note.disagreement().is_some()).await;
+    }
+}
+
+pub async fn update_health(robot: &Ferris) {
+    if let Ok(mut health) = robot.health.try_borrow_mut() {
+        poll_health(robot, &mut health).await;
+    }
+}
+
+/// keep the sensors fresh while we wait, and say whether we're ready
+pub async fn pre_match(robot: &Ferris) {
+    update_health(robot).await;
+    publish_readiness(robot).await;
+}
+
+/// Check every device for faults, subsystems that are busy keep their last report
+async fn poll_health(robot: &Ferris, health: &mut Health) {
+    if !health.due() {
+        return;
+    }
+
+    let mut alarms = Vec::new();
+    if let Ok(drivetrain) = robot.drivetrain.try_borrow() {
+        drivetrain.health(&mut alarms);
+        health.report("drivetrain", mem::take(&mut alarms));
+    }
+    if let Ok(intake) = robot.intake.try_borrow() {
+        intake.health(&mut alarms);
+        health.report("intake", mem::take(&mut alarms));
+    }
+    if let Ok(shooter) = robot.shooter.try_borrow() {
+        shooter.health(&mut alarms);
+        health.report("shooter", mem::take(&mut alarms));
+    }
+    if let Ok(climber) = robot.climber.try_borrow() {
+        climber.health(&mut alarms);
+        health.report("climber", mem::take(&mut alarms));
+    }
+
+    let alarms = health.alarms();
+    telemetry::put_number("alarm count", alarms.len() as f64).await;
+    telemetry::put_bool("healthy", health.healthy()).await;
+    if let Ok(alarms) = serde_json::to_string(&alarms) {
+        telemetry::put_text("alarms", &alarms).await;
+    }
+}
+
 /// Transfer note from intake to shooter
-pub async fn stage(intake: &mut Intake, shooter: &Shooter) {
-    intake.set_rollers(1.);
-    raise_intake(intake).await;
-    //intake.set_actuate(0.15);
-    //let _ = timeout(Duration::from_millis(200), wait(|| intake.at_limit())).await;
+pub async fn stage(intake: &mut Intake, shooter: &Shooter) -> Outcome {
+    let mut sequence = Sequence::new("stage", (intake, shooter), stop_handoff);
+    let (intake, shooter) = &mut *sequence;
 
-    sleep(Duration::from_millis(200)).await;
+    let outcome = async {
+        intake.set_rollers(1.);
+        raise_intake(intake).await;
+        //intake.set_actuate(0.15);
+        //let _ = timeout(Duration::from_millis(200), wait(|| intake.at_limit())).await;
 
-    intake.set_rollers(-0.13);
-    shooter.set_feeder(-0.34);
-    wait(|| shooter.contains_note()).await;
-    intake.set_rollers(0.0);
-    intake.set_actuate(0.0);
+        sleep(Duration::from_millis(200)).await;
 
-    shooter.set_feeder(-0.10);
-    wait(|| !shooter.contains_note()).await;
-    shooter.set_feeder(0.0);
+        feed_note(intake, shooter, -0.13).await?;
+        intake.set_rollers(0.0);
+        intake.set_actuate(0.0);
+
+        shooter.index_note().await
+    }
+    .await;
+
+    let outcome = sequence.finish(outcome);
+    telemetry::put_text("stage", describe(outcome)).await;
+    outcome
+}
+
+/// stop everything that touches the note on its way to the shooter
+fn stop_handoff((intake, shooter): &mut (&mut Intake, &Shooter)) {
+    intake.stop_rollers();
+    intake.set_actuate(0.);
+    shooter.stop_feeder();
+}
+
+/// Push the note from the raised intake onto the beam break
+///
+/// Backs the note out and tries again if the rollers or feeder jam,
+/// up to `JAM_RETRIES` times. Timing out with everything still turning means
+/// there was no note to feed, so that gives up straight away
+async fn feed_note(intake: &mut Intake, shooter: &Shooter, rollers: f64) -> Outcome {
+    let mut retries = 0;
+    telemetry::put_number("handoff retries", 0.).await;
+
+    loop {
+        intake.set_rollers(rollers);
+        shooter.set_feeder(-0.34);
+
+        match wait_for_note(intake, shooter).await {
+            Err(Interrupted::Jammed) if retries < JAM_RETRIES => {
+                retries += 1;
+                println!("note jammed, retry {retries}");
+                telemetry::put_number("handoff retries", retries as f64).await;
+                clear_jam(intake, shooter).await;
+            }
+            outcome => return outcome,
+        }
+    }
+}
+
+/// wait for the beam break, giving up early if anything stops turning
+async fn wait_for_note(intake: &mut Intake, shooter: &Shooter) -> Outcome {
+    let started = Instant::now();
+    let mut feeder = JamDetector::feeder();
+    let mut rollers = JamDetector::rollers();
+
+    loop {
+        if shooter.contains_note() {
+            return Ok(());
+        }
+
+        let feeder_jammed = feeder.update(shooter.feeder_current(), shooter.feeder_velocity());
+        let rollers_jammed = rollers.update(intake.roller_current(), intake.roller_velocity());
+        if feeder_jammed || rollers_jammed {
+            return Err(Interrupted::Jammed);
+        }
+
+        if started.elapsed().as_secs_f64() > HANDOFF_TIMEOUT {
+            return Err(Interrupted::TimedOut);
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+}
+
+/// back the note out of the feeder and pull it back into the intake
+async fn clear_jam(intake: &mut Intake, shooter: &Shooter) {
+    intake.set_rollers(0.4);
+    shooter.set_feeder(0.3);
+    sleep(Duration::from_secs_f64(REVERSE_TIME)).await;
+
+    shooter.set_feeder(0.);
+    intake.set_rollers(0.6);
+    sleep(Duration::from_secs_f64(RESEAT_TIME)).await;
+
+    intake.set_rollers(0.);
 }
 
-pub fn stop_all(robot: &Ferris) {
-    robot.drivetrain.borrow().stop();
-    robot.intake.borrow().stop();
-    robot.shooter.borrow().stop();
-    robot.climber.borrow().stop();
+/// Free a stuck note and try to stage it again, spitting it out the intake if that fails
+pub async fn unjam(intake: &mut Intake, shooter: &Shooter) -> Outcome {
+    let mut sequence = Sequence::new("unjam", (intake, shooter), stop_handoff);
+    let (intake, shooter) = &mut *sequence;
+
+    let outcome = async {
+        clear_jam(intake, shooter).await;
+        raise_intake(intake).await;
+
+        match feed_note(intake, shooter, -0.13).await {
+            Ok(()) => {
+                intake.set_rollers(0.);
+                shooter.index_note().await
+            }
+            Err(Interrupted::Jammed) => {
+                println!("couldn't clear the jam, ejecting");
+                lower_intake(intake).await;
+                shooter.set_feeder(0.3);
+                intake.set_rollers(-1.);
+                sleep(Duration::from_secs_f64(EJECT_TIME)).await;
+                Err(Interrupted::Jammed)
+            }
+            // nothing came through, there's nothing to eject
+            Err(interrupted) => Err(interrupted),
+        }
+    }
+    .await;
+
+    let outcome = sequence.finish(outcome);
+    telemetry::put_text("unjam", describe(outcome)).await;
+    outcome
+}
+
+/// Deploy the amp bar, and only feed the note once it's out
+pub async fn score_amp(shooter: &mut Shooter) {
+    let dt = Duration::from_millis(20);
+
+    shooter.set_shooter(0.225);
+    shooter.deploy_amp();
+    loop {
+        shooter.update_amp(&dt);
+        // if homing failed we can't trust at_goal, go by position
+        if shooter.amp_at_goal() || !shooter.amp_moving() && shooter.amp_deployed() {
+            break;
+        }
+        sleep(dt).await;
+    }
+
+    shooter.set_feeder(-1.);
+    wait(|| !shooter.contains_note()).await;
+    sleep(Duration::from_millis(300)).await;
+    shooter.stop_feeder();
+    shooter.stop_shooter();
+
+    shooter.stow_amp();
+    while shooter.amp_moving() {
+        shooter.update_amp(&dt);
+        sleep(dt).await;
+    }
 }
 
 async fn grab_full(robot: Ferris) -> anyhow::Result<()> {
@@ -255,42 +737,55 @@ async fn grab_full(robot: Ferris) -> anyhow::Result<()> {
     let shooter = robot.shooter.clone();
     let mut intake = intake.deref().try_borrow_mut()?;
     let shooter = shooter.deref().try_borrow()?;
-    lower_intake(&mut intake).await;
-    intake.set_rollers(0.6);
-    wait(|| intake.running()).await;
-    wait(|| intake.stalled()).await;
-    intake.set_rollers(1.);
-    raise_intake(&mut intake).await;
-    sleep(Duration::from_millis(200)).await;
-    intake.set_rollers(-0.43);
-    shooter.set_feeder(-0.34);
-    wait(|| shooter.contains_note()).await;
-    intake.set_rollers(0.0);
-    intake.set_actuate(0.0);
-    shooter.set_feeder(-0.10);
-    wait(|| !shooter.contains_note()).await;
-    shooter.set_feeder(0.0);
+
+    let mut sequence = Sequence::new("grab full", (&mut *intake, &*shooter), stop_handoff);
+    let (intake, shooter) = &mut *sequence;
+
+    let outcome = async {
+        lower_intake(intake).await;
+        intake.set_rollers(0.6);
+        wait_for(
+            || intake.running(),
+            Duration::from_secs_f64(INTAKE_SPIN_UP_TIMEOUT),
+        )
+        .await?;
+        wait_for(
+            || intake.stalled(),
+            Duration::from_secs_f64(INTAKE_GRAB_TIMEOUT),
+        )
+        .await?;
+        intake.set_rollers(1.);
+        raise_intake(intake).await;
+        sleep(Duration::from_millis(200)).await;
+        feed_note(intake, shooter, -0.43).await?;
+        intake.set_rollers(0.0);
+        intake.set_actuate(0.0);
+        shooter.index_note().await
+    }
+    .await;
+
+    let outcome = sequence.finish(outcome);
+    telemetry::put_text("grab full", describe(outcome)).await;
+    outcome?;
     Ok(())
 }
 
 pub async fn lower_intake_trapezoidal(intake: &mut Intake) {
-    loop {
-        let dt = Duration::from_millis(20);
-        intake.actuate_to_trapezoid(Angle::new::<degree>(INTAKE_DOWN_GOAL), &dt);
-        sleep(dt).await;
-        if intake.actuate_position().get::<degree>() < INTAKE_DOWN_THRESHOLD {
-            return
+    let dt = Duration::from_millis(20);
+    while !intake.actuate_profiled(Angle::new::<degree>(INTAKE_DOWN_GOAL), &dt) {
+        if !intake.homed() {
+            return;
         }
+        sleep(dt).await;
     }
 }
 
 pub async fn raise_intake_trapezoidal(intake: &mut Intake) {
-    loop {
-        let dt = Duration::from_millis(20);
-        let trapd = intake.actuate_to_trapezoid_rdy(Angle::new::<degree>(INTAKE_UP_GOAL), &dt);
-        sleep(dt).await;
-        if intake.actuate_position().get::<degree>() > INTAKE_UP_THRESHOLD && trapd {
-            return
+    let dt = Duration::from_millis(20);
+    while !intake.actuate_profiled(Angle::new::<degree>(INTAKE_UP_GOAL), &dt) {
+        if !intake.homed() {
+            return;
         }
+        sleep(dt).await;
     }
 }
@@ -1,4 +1,11 @@
-use crate::{subsystems::Shooter, telemetry};
+use uom::si::{angle::degree, f64::Angle};
+
+use crate::{
+    aim::shot_map::Shot,
+    constants::drivetrain::AIM_TOLERANCE,
+    subsystems::{AmpBarState, Shooter},
+    telemetry,
+};
 
 use super::{Controllers, GamepadState};
 
@@ -10,6 +17,12 @@ pub struct ShooterControlState {
     pub firing: bool,
     gamepad_spinning: bool,
     gamepad_spinning_last: bool,
+    /// flywheel speeds for the current (virtual) distance to our speaker
+    pub shot: Option<Shot>,
+    /// set while the drivetrain is aiming at the speaker
+    pub aim_error: Option<Angle>,
+    /// battery is browning out, don't idle the flywheels
+    pub shedding: bool,
 }
 
 pub async fn control_shooter(
@@ -29,9 +42,29 @@ pub async fn control_shooter(
     let gamepad_spinning = &mut state.gamepad_spinning;
     telemetry::put_number("flywheel speed", shooter.get_velocity()).await;
     telemetry::put_bool("beam break: {}", shooter.contains_note()).await;
+    if let Some(offset) = shooter.note_offset() {
+        telemetry::put_number("note offset", offset).await;
+    }
+    telemetry::put_bool("note indexed", shooter.indexed()).await;
     telemetry::put_bool("flywheel state", *gamepad_spinning).await;
+    telemetry::put_number("amp position", shooter.amp_position()).await;
+    telemetry::put_bool("amp homed", shooter.amp_state() == AmpBarState::Homed).await;
 
-    if matches!(gamepad_state, GamepadState::Auto | GamepadState::Drive | GamepadState::Manual) {
+    let shot = state.shot;
+    if let Some(shot) = shot {
+        telemetry::put_number("speaker distance", shot.distance).await;
+        telemetry::put_number("shot map rpm", shot.top).await;
+    }
+
+    if right_drive.get(4) {
+        // shoot from here
+        if let Some(shot) = shot {
+            shooter.set_velocities(shot.top, shot.bottom);
+            gamepad.rumble_right((shot.top - shooter.get_velocity()) / 2000.);
+            *gamepad_spinning = true;
+        }
+        shooter.stow_amp();
+    } else if matches!(gamepad_state, GamepadState::Auto | GamepadState::Drive | GamepadState::Manual) {
         if gamepad.a() {
             // line shot
             if right_drive.get(2) {
@@ -79,7 +112,11 @@ pub async fn control_shooter(
             GamepadState::Auto | GamepadState::Manual | GamepadState::Drive
         ) && gamepad.right_bumper();
 
-    if *shooting && !*gamepad_spinning {
+    // idling the flywheels is the first thing to go when the battery sags,
+    // it picks back up once the battery recovers
+    let paused = state.shedding && !*firing;
+
+    if *shooting && !*gamepad_spinning && !paused {
         if shooter.amp_deployed() && !operator.get(5) {
             shooter.set_shooter(0.225)
         } else if right_drive.get(2) {
@@ -107,8 +144,17 @@ pub async fn control_shooter(
         }
     }
 
+    // hold the note until we're pointed at the speaker
+    let aligned = state
+        .aim_error
+        .map_or(true, |error| error.get::<degree>().abs() < AIM_TOLERANCE);
+    telemetry::put_bool("aligned", aligned).await;
+
+    // and until the amp bar is done moving
+    let aligned = aligned && !shooter.amp_moving();
+
     if !*staging {
-        if *firing {
+        if *firing && aligned {
             shooter.set_feeder(-1.);
         } else if matches!(gamepad_state, GamepadState::Manual) && gamepad.left_trigger() > 0. {
             shooter.set_feeder(gamepad.left_trigger());
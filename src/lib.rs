@@ -1,48 +1,60 @@
 #![feature(variant_count)]
 
+mod aim;
 mod auto;
 pub mod constants;
+mod crash;
+mod feedforward;
+mod field;
 mod input;
+mod mode;
+mod profile;
+mod scheduler;
 mod subsystems;
 mod swerve;
 pub mod telemetry;
+mod vision;
+mod watchdog;
+mod wrapper;
 
-use std::borrow::BorrowMut;
-
-use std::time::Duration;
-use std::time::Instant;
+use std::ops::Deref;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
 
-use auto::{run_auto, Auto};
-use constants::FPS_LIMIT;
-use constants::TELEMETRY_PORT;
+use constants::{TELEMETRY_PORT, WATCHDOG_TIMEOUT};
 use input::{Controllers, Ferris, GamepadState};
+use mode::Mode;
+use scheduler::{Scheduler, Task};
 
 use frcrs::observe_user_program_starting;
 use frcrs::refresh_data;
 
 use frcrs::hal_report;
 use frcrs::init_hal;
-use frcrs::input::{Gamepad, Joystick, RobotState};
+use frcrs::input::{Gamepad, Joystick};
 
-use num_traits::{FromPrimitive, ToPrimitive};
-
-use telemetry::Data;
 use tokio::time::sleep;
 
-use crate::input::container;
+use crate::input::{container, manage_power, pre_match, report_panics, report_stalls, update_note};
+use crate::subsystems::{neutral_all, start_neutral, Alarm, Severity};
+use crate::watchdog::Watchdog;
 
-use std::ops::Deref;
 use tokio::task::{self};
 
 //pub extern "system" fn entrypoint <'local>(mut env: JNIEnv<'local>, class: JClass<'local>) {
 
 pub fn entrypoint() {
+    crash::install();
+    start_neutral();
+
     let executor = tokio::runtime::Runtime::new().unwrap();
     let local = task::LocalSet::new();
 
     let controller = local.run_until(async {
-        if !init_hal() {
-            panic!("Failed to init HAL")
+        // keep going without it, so there's still a dashboard to say what's wrong
+        let hal_ready = init_hal();
+        if !hal_ready {
+            println!("Failed to init HAL");
         }
 
         hal_report(2, 7, 0, "2024.2.1".to_string());
@@ -62,73 +74,93 @@ pub fn entrypoint() {
         let mut robot = Ferris::new();
         observe_user_program_starting();
 
+        if !hal_ready {
+            let alarm = Alarm::new("hal", "failed to initialize", Severity::Critical);
+            robot.health.deref().borrow_mut().report("hal", vec![alarm]);
+        }
+
         let router = telemetry::server().with_state(robot.telemetry.clone());
 
         executor
             .spawn(async move {
-                let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", TELEMETRY_PORT))
-                    .await
-                    .unwrap();
-                axum::serve(listener, router).await.unwrap();
+                let address = format!("0.0.0.0:{}", TELEMETRY_PORT);
+                let listener = match tokio::net::TcpListener::bind(address).await {
+                    Ok(listener) => listener,
+                    Err(e) => {
+                        println!("couldn't start telemetry on port {TELEMETRY_PORT}: {e}");
+                        return;
+                    }
+                };
+                if let Err(e) = axum::serve(listener, router).await {
+                    println!("telemetry server stopped: {e}");
+                }
             })
             .abort_handle();
 
-        let mut auto = None;
+        let mut lifecycle = robot.lifecycle();
+        let watchdog = Watchdog::spawn(Duration::from_secs_f64(WATCHDOG_TIMEOUT), neutral_all);
 
-        let mut last_loop = Instant::now();
-        let mut dt = Duration::from_millis(0);
+        let mut scheduler = Scheduler::default();
         loop {
-            refresh_data();
-
-            let state = RobotState::get();
-
-            if state.enabled() && state.teleop() && !state.test() {
-                container(&mut controllers, &mut robot, &local, dt.clone()).await;
-            };
-
-            if state.enabled() && state.auto() {
-                if let None = auto {
-                    let robot = robot.clone();
-
-                    let chosen = if let Data::Picker(picker) = robot
-                        .telemetry
-                        .read()
-                        .await
-                        .data
-                        .get("auto chooser")
-                        .unwrap()
-                    {
-                        picker.selected.parse().unwrap()
-                    } else {
-                        println!("auto chooser not found");
-                        Auto::default().to_usize().unwrap()
-                    };
-
-                    let chosen = Auto::from_usize(chosen).unwrap();
-
-                    let run = run_auto(chosen, robot);
-                    auto = Some(local.spawn_local(run).abort_handle());
-                    //auto = Some(local.spawn_local(auto_long(robot.clone())).abort_handle());
+            watchdog.feed();
+            report_stalls(&robot, &watchdog);
+            report_panics(&robot);
+
+            if let Some(dt) = scheduler.begin(Task::Control) {
+                watchdog.enter(Task::Control.name());
+                refresh_data();
+
+                let mode = Mode::get();
+                if lifecycle.update(mode, &robot) {
+                    telemetry::put_text("mode", mode.name()).await;
                 }
-            } else if let Some(auto) = auto.take() {
-                auto.abort();
-            };
+                scheduler.begin_section("note");
+                update_note(&robot).await;
+                scheduler.end_section("note");
+
+                match mode {
+                    Mode::Teleop => {
+                        container(&mut controllers, &mut robot, &local, dt, &mut scheduler).await
+                    }
+                    Mode::Disabled => pre_match(&robot).await,
+                    _ => {}
+                }
+
+                scheduler.end(Task::Control);
+            }
 
-            if state.test() && state.enabled() {
-                robot.drivetrain.deref().borrow_mut().write_absolute();
+            if scheduler.begin(Task::Power).is_some() {
+                watchdog.enter(Task::Power.name());
+                let mode = lifecycle.mode().unwrap_or(Mode::Disabled);
+                manage_power(&robot, mode.enabled(), mode == Mode::Auto).await;
+                scheduler.end(Task::Power);
             }
 
-            dt = last_loop.elapsed();
-            let elapsed = dt.as_secs_f64();
-            let left = (1. / FPS_LIMIT - elapsed).max(0.);
+            if scheduler.begin(Task::Telemetry).is_some() {
+                watchdog.enter(Task::Telemetry.name());
+                scheduler.begin_section("timing");
+                scheduler.publish().await;
+                scheduler.end_section("timing");
+
+                scheduler.begin_section("auto confirmation");
+                auto::publish_confirmation(&robot.telemetry).await;
+                scheduler.end_section("auto confirmation");
+                scheduler.end(Task::Telemetry);
+            }
 
-            telemetry::put_number("rio load", last_loop.elapsed().as_secs_f64() / (1./FPS_LIMIT)).await;
-            sleep(Duration::from_secs_f64(left)).await;
-            telemetry::put_number("loop rate (hz)", 1. / last_loop.elapsed().as_secs_f64()).await;
-            last_loop = Instant::now();
-            //println!("hz {}", 1./last_loop.elapsed().as_secs_f64());
+            // the auto and sequences spawned on `local` run while we wait
+            watchdog.enter("spawned tasks");
+            if let Some(deadline) = scheduler.next_deadline() {
+                sleep(deadline.saturating_duration_since(Instant::now())).await;
+            }
         }
     });
 
-    executor.block_on(controller);
+    // anything else that panics is caught and the loop carries on, this one
+    // leaves nothing driving the motors
+    let ended = panic::catch_unwind(AssertUnwindSafe(|| executor.block_on(controller)));
+    if let Err(panic) = ended {
+        neutral_all();
+        panic::resume_unwind(panic);
+    }
 }
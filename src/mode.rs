@@ -0,0 +1,298 @@
+use frcrs::input::RobotState;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Disabled,
+    Auto,
+    Teleop,
+    Test,
+}
+
+impl Mode {
+    pub fn get() -> Self {
+        let state = RobotState::get();
+        if !state.enabled() {
+            Mode::Disabled
+        } else if state.test() {
+            Mode::Test
+        } else if state.auto() {
+            Mode::Auto
+        } else if state.teleop() {
+            Mode::Teleop
+        } else {
+            Mode::Disabled
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::Disabled => "disabled",
+            Mode::Auto => "auto",
+            Mode::Teleop => "teleop",
+            Mode::Test => "test",
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        *self != Mode::Disabled
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// once, on entering the mode
+    Init,
+    /// every control loop while in the mode, after any init
+    Periodic,
+    /// once, on leaving the mode, before the next mode's init
+    Exit,
+}
+
+type Hook<T> = Box<dyn FnMut(&T)>;
+type Check<T> = Box<dyn FnMut(&T) -> bool>;
+
+struct Handler<T> {
+    mode: Mode,
+    phase: Phase,
+    name: &'static str,
+    hook: Hook<T>,
+}
+
+/// Runs registered handlers as the robot moves between modes
+///
+/// `stop` runs on every transition into disabled, after the last mode's exit
+/// handlers and before disabled's init, so nothing the last mode left running
+/// survives it. It also runs whenever something registered with `stop_when`
+/// ends on its own, panicked or not
+pub struct Lifecycle<T> {
+    mode: Option<Mode>,
+    stop: Hook<T>,
+    handlers: Vec<Handler<T>>,
+    always: Vec<Hook<T>>,
+    ended: Vec<(Mode, &'static str, Check<T>)>,
+}
+
+impl<T> Lifecycle<T> {
+    pub fn new(stop: impl FnMut(&T) + 'static) -> Self {
+        Self {
+            mode: None,
+            stop: Box::new(stop),
+            handlers: Vec::new(),
+            always: Vec::new(),
+            ended: Vec::new(),
+        }
+    }
+
+    pub fn on(
+        &mut self,
+        mode: Mode,
+        phase: Phase,
+        name: &'static str,
+        hook: impl FnMut(&T) + 'static,
+    ) {
+        self.handlers.push(Handler {
+            mode,
+            phase,
+            name,
+            hook: Box::new(hook),
+        });
+    }
+
+    /// every control loop whatever the mode, before that mode's periodic handlers
+    pub fn always(&mut self, hook: impl FnMut(&T) + 'static) {
+        self.always.push(Box::new(hook));
+    }
+
+    /// Stop everything once `ended` says whatever `mode` runs is over,
+    /// rather than leave it at its last output until the mode changes.
+    /// `stop` has to clear whatever `ended` looks at, or it stops every loop
+    pub fn stop_when(
+        &mut self,
+        mode: Mode,
+        name: &'static str,
+        ended: impl FnMut(&T) -> bool + 'static,
+    ) {
+        self.ended.push((mode, name, Box::new(ended)));
+    }
+
+    /// the mode as of the last `update`
+    pub fn mode(&self) -> Option<Mode> {
+        self.mode
+    }
+
+    /// Run exit and init handlers if the mode changed, then the periodic
+    /// ones, true if it changed
+    pub fn update(&mut self, mode: Mode, context: &T) -> bool {
+        let previous = self.mode.replace(mode);
+        let changed = previous != Some(mode);
+
+        if changed {
+            println!(
+                "{} -> {}",
+                previous.map_or("starting", |mode| mode.name()),
+                mode.name()
+            );
+
+            if let Some(previous) = previous {
+                self.run(previous, Phase::Exit, context);
+            }
+            if mode == Mode::Disabled {
+                (self.stop)(context);
+            }
+            self.run(mode, Phase::Init, context);
+        }
+
+        for hook in &mut self.always {
+            hook(context);
+        }
+        self.run(mode, Phase::Periodic, context);
+
+        for (when, name, ended) in &mut self.ended {
+            if *when == mode && ended(context) {
+                println!("{name} ended, stopping");
+                (self.stop)(context);
+            }
+        }
+        changed
+    }
+
+    fn run(&mut self, mode: Mode, phase: Phase, context: &T) {
+        for handler in &mut self.handlers {
+            if handler.mode == mode && handler.phase == phase {
+                if phase != Phase::Periodic {
+                    println!("{} {:?} {:?}", handler.name, mode, phase);
+                }
+                (handler.hook)(context);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        time::Duration,
+    };
+
+    use tokio::task::{JoinHandle, LocalSet};
+
+    use super::{Lifecycle, Mode, Phase};
+
+    fn register(lifecycle: &mut Lifecycle<RefCell<Vec<&'static str>>>) {
+        lifecycle.on(Mode::Auto, Phase::Init, "auto", |log| {
+            log.borrow_mut().push("auto init")
+        });
+        lifecycle.on(Mode::Auto, Phase::Periodic, "auto", |log| {
+            log.borrow_mut().push("auto periodic")
+        });
+        lifecycle.on(Mode::Auto, Phase::Exit, "auto", |log| {
+            log.borrow_mut().push("auto exit")
+        });
+        lifecycle.on(Mode::Disabled, Phase::Init, "disabled", |log| {
+            log.borrow_mut().push("disabled init")
+        });
+    }
+
+    #[test]
+    fn runs_in_order() {
+        let events = RefCell::new(Vec::new());
+        let mut lifecycle = Lifecycle::new(|log: &RefCell<Vec<_>>| log.borrow_mut().push("stop"));
+        register(&mut lifecycle);
+
+        assert!(lifecycle.update(Mode::Auto, &events));
+        assert!(!lifecycle.update(Mode::Auto, &events));
+        assert!(lifecycle.update(Mode::Disabled, &events));
+
+        assert_eq!(
+            *events.borrow(),
+            vec![
+                "auto init",
+                "auto periodic",
+                "auto periodic",
+                "auto exit",
+                "stop",
+                "disabled init",
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_every_time_it_disables() {
+        let events = RefCell::new(Vec::new());
+        let mut lifecycle = Lifecycle::new(|log: &RefCell<Vec<_>>| log.borrow_mut().push("stop"));
+
+        for mode in [
+            Mode::Disabled,
+            Mode::Teleop,
+            Mode::Disabled,
+            Mode::Test,
+            Mode::Disabled,
+        ] {
+            lifecycle.update(mode, &events);
+        }
+
+        assert_eq!(events.borrow().len(), 3);
+    }
+
+    #[test]
+    fn always_runs_in_every_mode() {
+        let events = RefCell::new(Vec::new());
+        let mut lifecycle = Lifecycle::new(|_: &RefCell<Vec<_>>| {});
+        register(&mut lifecycle);
+        lifecycle.always(|log| log.borrow_mut().push("always"));
+
+        for mode in [Mode::Disabled, Mode::Auto, Mode::Teleop, Mode::Test] {
+            lifecycle.update(mode, &events);
+        }
+
+        let events = events.borrow();
+        assert_eq!(events.iter().filter(|e| **e == "always").count(), 4);
+        let auto = events.iter().position(|e| *e == "auto periodic").unwrap();
+        assert_eq!(events[auto - 1], "always");
+    }
+
+    #[derive(Default)]
+    struct Robot {
+        auto: RefCell<Option<JoinHandle<()>>>,
+        stops: Cell<u32>,
+    }
+
+    #[test]
+    fn stops_when_a_task_panics() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let local = LocalSet::new();
+
+        let robot = Robot::default();
+        let mut lifecycle = Lifecycle::new(|robot: &Robot| {
+            robot.auto.take();
+            robot.stops.set(robot.stops.get() + 1);
+        });
+        lifecycle.on(Mode::Auto, Phase::Init, "auto", |robot: &Robot| {
+            robot.auto.replace(Some(tokio::task::spawn_local(async {
+                panic!("auto went wrong");
+            })));
+        });
+        lifecycle.stop_when(Mode::Auto, "auto", |robot: &Robot| {
+            robot
+                .auto
+                .borrow()
+                .as_ref()
+                .is_some_and(JoinHandle::is_finished)
+        });
+
+        local.block_on(&runtime, async {
+            lifecycle.update(Mode::Auto, &robot);
+            assert_eq!(robot.stops.get(), 0);
+
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            lifecycle.update(Mode::Auto, &robot);
+            lifecycle.update(Mode::Auto, &robot);
+        });
+
+        assert_eq!(robot.stops.get(), 1);
+    }
+}
@@ -0,0 +1,337 @@
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct State {
+    pub position: f64,
+    pub velocity: f64,
+    pub acceleration: f64,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Constraints {
+    pub max_velocity: f64,
+    pub max_acceleration: f64,
+    /// only used by the s-curve profile
+    pub max_jerk: f64,
+}
+
+impl Constraints {
+    pub const fn new(max_velocity: f64, max_acceleration: f64) -> Self {
+        Self {
+            max_velocity,
+            max_acceleration,
+            max_jerk: f64::INFINITY,
+        }
+    }
+
+    pub const fn with_jerk(self, max_jerk: f64) -> Self {
+        Self { max_jerk, ..self }
+    }
+}
+
+/// fastest speed that can still stop within distance, decelerating once per dt
+fn stopping_velocity(distance: f64, max_acceleration: f64, dt: f64) -> f64 {
+    if dt <= 0. {
+        return (2. * max_acceleration * distance).sqrt();
+    }
+
+    let step = max_acceleration * dt * dt;
+    max_acceleration * dt * ((0.25 + 2. * distance / step).sqrt() - 0.5)
+}
+
+/// Setpoint generator that ramps towards a goal without exceeding velocity and acceleration
+///
+/// Units are whatever the mechanism uses, the profile starts from the
+/// measured position the first time it's stepped after a `reset`
+pub struct TrapezoidProfile {
+    constraints: Constraints,
+    state: Option<State>,
+    goal: Option<f64>,
+}
+
+impl TrapezoidProfile {
+    pub fn new(constraints: Constraints) -> Self {
+        Self {
+            constraints,
+            state: None,
+            goal: None,
+        }
+    }
+
+    /// forget the current setpoint, next step starts from the measurement
+    pub fn reset(&mut self) {
+        self.state = None;
+        self.goal = None;
+    }
+
+    pub fn state(&self) -> Option<State> {
+        self.state
+    }
+
+    /// the setpoint has reached the goal
+    pub fn finished(&self) -> bool {
+        match (self.state, self.goal) {
+            (Some(state), Some(goal)) => state.position == goal && state.velocity == 0.,
+            _ => false,
+        }
+    }
+
+    /// advance the setpoint by dt towards goal
+    pub fn step(&mut self, measured: f64, goal: f64, dt: &Duration) -> State {
+        let dt = dt.as_secs_f64();
+        let Constraints {
+            max_velocity,
+            max_acceleration,
+            ..
+        } = self.constraints;
+
+        let mut state = self.state.unwrap_or(State {
+            position: measured,
+            ..Default::default()
+        });
+        self.goal = Some(goal);
+
+        let remaining = goal - state.position;
+
+        // fastest we can be going and still stop at the goal
+        let desired = remaining.signum()
+            * max_velocity.min(stopping_velocity(remaining.abs(), max_acceleration, dt));
+
+        let max_change = max_acceleration * dt;
+        let velocity = state.velocity + (desired - state.velocity).clamp(-max_change, max_change);
+
+        let step = velocity * dt;
+        if remaining == 0. || step.abs() >= remaining.abs() && step.signum() == remaining.signum() {
+            state = State {
+                position: goal,
+                ..Default::default()
+            };
+        } else {
+            state = State {
+                position: state.position + step,
+                velocity,
+                acceleration: if dt > 0. {
+                    (velocity - state.velocity) / dt
+                } else {
+                    0.
+                },
+            };
+        }
+
+        self.state = Some(state);
+        state
+    }
+}
+
+/// Like `TrapezoidProfile`, but acceleration ramps at `max_jerk` for smoother starts and stops
+pub struct SCurveProfile {
+    constraints: Constraints,
+    state: Option<State>,
+    goal: Option<f64>,
+}
+
+impl SCurveProfile {
+    pub fn new(constraints: Constraints) -> Self {
+        Self {
+            constraints,
+            state: None,
+            goal: None,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.state = None;
+        self.goal = None;
+    }
+
+    pub fn state(&self) -> Option<State> {
+        self.state
+    }
+
+    pub fn finished(&self) -> bool {
+        match (self.state, self.goal) {
+            (Some(state), Some(goal)) => state.position == goal && state.velocity == 0.,
+            _ => false,
+        }
+    }
+
+    pub fn step(&mut self, measured: f64, goal: f64, dt: &Duration) -> State {
+        let dt = dt.as_secs_f64();
+        let Constraints {
+            max_velocity,
+            max_acceleration,
+            max_jerk,
+        } = self.constraints;
+
+        let state = self.state.unwrap_or(State {
+            position: measured,
+            ..Default::default()
+        });
+        self.goal = Some(goal);
+
+        let remaining = goal - state.position;
+        let direction = remaining.signum();
+
+        // distance covered while ramping acceleration back to zero
+        let ramp = state.velocity.abs() * max_acceleration / max_jerk;
+        let braking = (remaining.abs() - ramp).max(0.);
+        let desired_velocity =
+            direction * max_velocity.min(stopping_velocity(braking, max_acceleration, dt));
+
+        let desired_acceleration = if dt > 0. {
+            ((desired_velocity - state.velocity) / dt).clamp(-max_acceleration, max_acceleration)
+        } else {
+            0.
+        };
+        let max_change = max_jerk * dt;
+        let acceleration = state.acceleration
+            + (desired_acceleration - state.acceleration).clamp(-max_change, max_change);
+
+        let velocity = (state.velocity + acceleration * dt).clamp(-max_velocity, max_velocity);
+        let step = velocity * dt;
+
+        let arrived = remaining == 0.
+            || step.abs() >= remaining.abs() && step.signum() == remaining.signum()
+            || remaining.abs() < 1e-6 && velocity.abs() < max_acceleration * dt;
+
+        let state = if arrived {
+            State {
+                position: goal,
+                ..Default::default()
+            }
+        } else {
+            State {
+                position: state.position + step,
+                velocity,
+                acceleration,
+            }
+        };
+
+        self.state = Some(state);
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Constraints, SCurveProfile, TrapezoidProfile};
+
+    const DT: Duration = Duration::from_millis(20);
+
+    #[test]
+    fn trapezoid_reaches_goal() {
+        let mut profile = TrapezoidProfile::new(Constraints::new(2., 4.));
+
+        for _ in 0..500 {
+            profile.step(0., 3., &DT);
+            if profile.finished() {
+                break;
+            }
+        }
+
+        assert!(profile.finished());
+        assert_eq!(profile.state().unwrap().position, 3.);
+    }
+
+    #[test]
+    fn trapezoid_respects_constraints() {
+        let mut profile = TrapezoidProfile::new(Constraints::new(2., 4.));
+        let mut last = 0.;
+
+        for _ in 0..500 {
+            let state = profile.step(0., -5., &DT);
+            if profile.finished() {
+                break;
+            }
+            assert!(state.velocity.abs() <= 2. + 1e-9);
+            assert!((state.velocity - last).abs() <= 4. * DT.as_secs_f64() + 1e-9);
+            last = state.velocity;
+        }
+
+        assert_eq!(profile.state().unwrap().position, -5.);
+    }
+
+    #[test]
+    fn trapezoid_takes_expected_time() {
+        // 1s up to speed, 1s cruise, 1s down
+        let mut profile = TrapezoidProfile::new(Constraints::new(1., 1.));
+        let mut steps = 0;
+
+        while !profile.finished() {
+            profile.step(0., 2., &DT);
+            steps += 1;
+            assert!(steps < 1000);
+        }
+
+        let elapsed = steps as f64 * DT.as_secs_f64();
+        assert!((elapsed - 3.).abs() < 0.1, "took {elapsed}s");
+    }
+
+    #[test]
+    fn trapezoid_starts_from_measured() {
+        let mut profile = TrapezoidProfile::new(Constraints::new(1., 1.));
+
+        let state = profile.step(10., 20., &DT);
+        assert!(state.position >= 10. && state.position < 10.1);
+
+        profile.reset();
+        let state = profile.step(-4., 20., &DT);
+        assert!(state.position >= -4. && state.position < -3.9);
+    }
+
+    #[test]
+    fn trapezoid_retargets_while_moving() {
+        let mut profile = TrapezoidProfile::new(Constraints::new(2., 4.));
+
+        for _ in 0..20 {
+            profile.step(0., 10., &DT);
+        }
+        assert!(profile.state().unwrap().velocity > 0.);
+
+        for _ in 0..500 {
+            profile.step(0., 0., &DT);
+        }
+
+        assert!(profile.finished());
+        assert_eq!(profile.state().unwrap().position, 0.);
+    }
+
+    #[test]
+    fn scurve_reaches_goal() {
+        let mut profile = SCurveProfile::new(Constraints::new(2., 4.).with_jerk(20.));
+
+        for _ in 0..1000 {
+            profile.step(0., 3., &DT);
+            if profile.finished() {
+                break;
+            }
+        }
+
+        assert!(profile.finished());
+    }
+
+    #[test]
+    fn scurve_respects_constraints() {
+        let constraints = Constraints::new(2., 4.).with_jerk(20.);
+        let mut profile = SCurveProfile::new(constraints);
+        let mut last = 0.;
+        let mut overshoot: f64 = 0.;
+
+        for _ in 0..1000 {
+            let state = profile.step(0., 5., &DT);
+            overshoot = overshoot.max(state.position - 5.);
+            if profile.finished() {
+                break;
+            }
+            assert!(state.velocity.abs() <= 2. + 1e-9);
+            assert!(state.acceleration.abs() <= 4. + 1e-9);
+            assert!((state.acceleration - last).abs() <= 20. * DT.as_secs_f64() + 1e-9);
+            last = state.acceleration;
+        }
+
+        assert!(profile.finished());
+        assert!(overshoot < 0.01);
+    }
+}
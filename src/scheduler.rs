@@ -0,0 +1,363 @@
+use std::{
+    collections::BTreeMap,
+    mem,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::constants::{scheduler::*, FPS_LIMIT};
+use crate::telemetry;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Task {
+    /// controls, auto and everything that moves the robot
+    Control,
+    /// battery and power distribution
+    Power,
+    /// timing stats and anything else only people look at
+    Telemetry,
+}
+
+impl Task {
+    pub const ALL: [Task; mem::variant_count::<Task>()] =
+        [Task::Control, Task::Power, Task::Telemetry];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Task::Control => "control",
+            Task::Power => "power",
+            Task::Telemetry => "telemetry",
+        }
+    }
+
+    /// hz
+    pub fn rate(&self) -> f64 {
+        match self {
+            Task::Control => FPS_LIMIT,
+            Task::Power => POWER_RATE,
+            Task::Telemetry => TELEMETRY_RATE,
+        }
+    }
+
+    pub fn period(&self) -> Duration {
+        Duration::from_secs_f64(1. / self.rate())
+    }
+}
+
+/// Run counts by execution time, bucketed by `HISTOGRAM_BUCKETS`
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Histogram {
+    /// one more than the buckets, for anything slower than the last edge
+    pub counts: [u64; HISTOGRAM_BUCKETS.len() + 1],
+}
+
+impl Histogram {
+    pub fn record(&mut self, milliseconds: f64) {
+        let bucket = HISTOGRAM_BUCKETS
+            .iter()
+            .position(|edge| milliseconds <= *edge)
+            .unwrap_or(HISTOGRAM_BUCKETS.len());
+        self.counts[bucket] += 1;
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TaskStats {
+    pub runs: u64,
+    /// took longer than its period
+    pub overruns: u64,
+    /// periods that went by without a run, because something else was slow
+    pub skipped: u64,
+    /// milliseconds
+    pub last: f64,
+    pub max: f64,
+    pub total: f64,
+    /// hz, measured between the last two runs
+    pub rate: f64,
+    pub histogram: Histogram,
+}
+
+impl TaskStats {
+    /// milliseconds
+    pub fn mean(&self) -> f64 {
+        if self.runs == 0 {
+            return 0.;
+        }
+        self.total / self.runs as f64
+    }
+}
+
+/// Timing for one named part of a task
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SectionStats {
+    pub runs: u64,
+    /// milliseconds
+    pub last: f64,
+    pub max: f64,
+    pub total: f64,
+    pub histogram: Histogram,
+}
+
+impl SectionStats {
+    /// milliseconds
+    pub fn mean(&self) -> f64 {
+        if self.runs == 0 {
+            return 0.;
+        }
+        self.total / self.runs as f64
+    }
+
+    fn record(&mut self, milliseconds: f64) {
+        self.runs += 1;
+        self.last = milliseconds;
+        self.max = self.max.max(milliseconds);
+        self.total += milliseconds;
+        self.histogram.record(milliseconds);
+    }
+}
+
+#[derive(Default)]
+struct SectionState {
+    started: Option<Instant>,
+    stats: SectionStats,
+}
+
+#[derive(Default)]
+struct TaskState {
+    next: Option<Instant>,
+    last_start: Option<Instant>,
+    started: Option<Instant>,
+    stats: TaskStats,
+}
+
+/// Runs each `Task` at its own rate off one loop, timing every run
+///
+/// Tasks that fall behind skip the periods they missed rather than
+/// running back to back to catch up. Parts of a task can be timed on their
+/// own as sections, registered by name the first time they run
+#[derive(Default)]
+pub struct Scheduler {
+    tasks: [TaskState; mem::variant_count::<Task>()],
+    sections: BTreeMap<&'static str, SectionState>,
+}
+
+impl Scheduler {
+    /// if `task` is due, mark it started and return the time since it last
+    /// started
+    pub fn begin(&mut self, task: Task) -> Option<Duration> {
+        self.begin_at(task, Instant::now())
+    }
+
+    pub fn begin_at(&mut self, task: Task, now: Instant) -> Option<Duration> {
+        let period = task.period();
+        let state = &mut self.tasks[task as usize];
+
+        let next = state.next.unwrap_or(now);
+        if now < next {
+            return None;
+        }
+
+        let behind = (now - next).as_secs_f64() / period.as_secs_f64();
+        state.stats.skipped += behind as u64;
+        state.next = Some(next + period.mul_f64(behind.floor() + 1.));
+
+        let dt = state.last_start.map_or(period, |last| now - last);
+        state.stats.rate = 1. / dt.as_secs_f64().max(f64::EPSILON);
+        state.last_start = Some(now);
+        state.started = Some(now);
+
+        Some(dt)
+    }
+
+    pub fn end(&mut self, task: Task) {
+        self.end_at(task, Instant::now());
+    }
+
+    pub fn end_at(&mut self, task: Task, now: Instant) {
+        let state = &mut self.tasks[task as usize];
+        let Some(started) = state.started.take() else {
+            return;
+        };
+
+        let elapsed = now - started;
+        let milliseconds = elapsed.as_secs_f64() * 1000.;
+        let stats = &mut state.stats;
+        stats.runs += 1;
+        stats.last = milliseconds;
+        stats.max = stats.max.max(milliseconds);
+        stats.total += milliseconds;
+        stats.histogram.record(milliseconds);
+
+        if elapsed > task.period() {
+            stats.overruns += 1;
+            println!(
+                "{} overran: {milliseconds:.2}ms of {:.2}ms",
+                task.name(),
+                task.period().as_secs_f64() * 1000.
+            );
+        }
+    }
+
+    pub fn stats(&self, task: Task) -> &TaskStats {
+        &self.tasks[task as usize].stats
+    }
+
+    /// start timing `section`
+    pub fn begin_section(&mut self, section: &'static str) {
+        self.begin_section_at(section, Instant::now());
+    }
+
+    pub fn begin_section_at(&mut self, section: &'static str, now: Instant) {
+        self.sections.entry(section).or_default().started = Some(now);
+    }
+
+    pub fn end_section(&mut self, section: &'static str) {
+        self.end_section_at(section, Instant::now());
+    }
+
+    pub fn end_section_at(&mut self, section: &'static str, now: Instant) {
+        let Some(state) = self.sections.get_mut(section) else {
+            return;
+        };
+        if let Some(started) = state.started.take() {
+            state.stats.record((now - started).as_secs_f64() * 1000.);
+        }
+    }
+
+    /// every section that's run, by name
+    pub fn sections(&self) -> impl Iterator<Item = (&'static str, &SectionStats)> {
+        self.sections
+            .iter()
+            .map(|(name, state)| (*name, &state.stats))
+    }
+
+    /// when the next task is due
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.tasks.iter().filter_map(|state| state.next).min()
+    }
+
+    /// fraction of the time spent running tasks, from their mean times
+    pub fn load(&self) -> f64 {
+        Task::ALL
+            .iter()
+            .map(|task| self.stats(*task).mean() * task.rate() / 1000.)
+            .sum()
+    }
+
+    pub async fn publish(&self) {
+        telemetry::put_number("rio load", self.load()).await;
+        telemetry::put_number("loop rate (hz)", self.stats(Task::Control).rate).await;
+
+        for task in Task::ALL {
+            let stats = self.stats(task);
+            let name = task.name();
+            telemetry::put_number(&format!("{name} time (ms)"), stats.last).await;
+            telemetry::put_number(&format!("{name} max time (ms)"), stats.max).await;
+            telemetry::put_number(&format!("{name} overruns"), stats.overruns as f64).await;
+        }
+        for (name, stats) in self.sections() {
+            telemetry::put_number(&format!("{name} time (ms)"), stats.last).await;
+            telemetry::put_number(&format!("{name} max time (ms)"), stats.max).await;
+        }
+
+        let tasks: BTreeMap<&str, &TaskStats> = Task::ALL
+            .iter()
+            .map(|task| (task.name(), self.stats(*task)))
+            .collect();
+        let sections: BTreeMap<&str, &SectionStats> = self.sections().collect();
+        let timing = json!({
+            "buckets": HISTOGRAM_BUCKETS,
+            "tasks": tasks,
+            "sections": sections,
+        });
+        if let Ok(timing) = serde_json::to_string(&timing) {
+            telemetry::put_text("task timing", &timing).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{Histogram, Scheduler, Task};
+
+    #[test]
+    fn runs_at_its_own_rate() {
+        let start = Instant::now();
+        let mut scheduler = Scheduler::default();
+
+        let mut control = 0;
+        let mut telemetry = 0;
+        for tick in 0..1000 {
+            let now = start + Duration::from_millis(tick);
+            if scheduler.begin_at(Task::Control, now).is_some() {
+                control += 1;
+                scheduler.end_at(Task::Control, now);
+            }
+            if scheduler.begin_at(Task::Telemetry, now).is_some() {
+                telemetry += 1;
+                scheduler.end_at(Task::Telemetry, now);
+            }
+        }
+
+        assert_eq!(control, 250);
+        assert_eq!(telemetry, 50);
+    }
+
+    #[test]
+    fn counts_overruns_and_skips() {
+        let start = Instant::now();
+        let mut scheduler = Scheduler::default();
+
+        scheduler.begin_at(Task::Control, start).unwrap();
+        scheduler.end_at(Task::Control, start + Duration::from_millis(10));
+
+        // 4ms periods, so two and a half went by while it ran
+        let dt = scheduler.begin_at(Task::Control, start + Duration::from_millis(10));
+        assert_eq!(dt, Some(Duration::from_millis(10)));
+        assert!(scheduler
+            .begin_at(Task::Control, start + Duration::from_millis(11))
+            .is_none());
+
+        let stats = scheduler.stats(Task::Control);
+        assert_eq!(stats.overruns, 1);
+        assert_eq!(stats.skipped, 1);
+        assert_eq!(stats.max, 10.);
+    }
+
+    #[test]
+    fn times_sections_apart() {
+        let start = Instant::now();
+        let mut scheduler = Scheduler::default();
+
+        scheduler.begin_at(Task::Control, start).unwrap();
+        scheduler.begin_section_at("drivetrain", start);
+        scheduler.end_section_at("drivetrain", start + Duration::from_millis(2));
+        scheduler.begin_section_at("shooter", start + Duration::from_millis(2));
+        scheduler.end_section_at("shooter", start + Duration::from_millis(3));
+        scheduler.end_at(Task::Control, start + Duration::from_millis(3));
+
+        // ending one that never started changes nothing
+        scheduler.end_section_at("climber", start);
+
+        let sections: Vec<_> = scheduler
+            .sections()
+            .map(|(name, stats)| (name, stats.runs, stats.last))
+            .collect();
+        assert_eq!(sections, vec![("drivetrain", 1, 2.), ("shooter", 1, 1.)]);
+        assert_eq!(scheduler.stats(Task::Control).last, 3.);
+    }
+
+    #[test]
+    fn histogram_buckets() {
+        let mut histogram = Histogram::default();
+        histogram.record(0.1);
+        histogram.record(0.75);
+        histogram.record(100.);
+
+        assert_eq!(histogram.counts, [1, 0, 1, 0, 0, 0, 0, 1]);
+    }
+}
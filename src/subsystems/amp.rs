@@ -0,0 +1,174 @@
+use std::time::{Duration, Instant};
+
+use crate::constants::power::AMP_BAR_CURRENT_LIMIT;
+use crate::constants::{amp::*, AMP_BAR};
+use crate::profile::{Constraints, SCurveProfile};
+use frcrs::rev::MotorType::Brushless;
+use frcrs::rev::Spark;
+use uom::si::angle::revolution;
+use uom::si::f64::Angle;
+
+use super::{check_spark, configure_spark, sheddable_output, Alarm};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AmpBarState {
+    Unhomed,
+    Homing(Instant),
+    Homed,
+    HomingFailed,
+}
+
+pub struct AmpBar {
+    motor: Spark,
+    state: AmpBarState,
+    /// encoder reading at the stowed hard stop
+    zero: f64,
+
+    goal: Option<f64>,
+    profile: SCurveProfile,
+}
+
+impl AmpBar {
+    pub fn new() -> Self {
+        let motor = Spark::new(AMP_BAR, Brushless);
+        configure_spark(&motor);
+
+        Self {
+            motor,
+            state: AmpBarState::Unhomed,
+            zero: 0.,
+
+            goal: None,
+            profile: SCurveProfile::new(
+                Constraints::new(MAX_VELOCITY, MAX_ACCELERATION).with_jerk(MAX_JERK),
+            ),
+        }
+    }
+
+    pub fn health(&self, alarms: &mut Vec<Alarm>) {
+        check_spark(alarms, "amp bar", &self.motor, AMP_BAR_CURRENT_LIMIT);
+    }
+
+    pub fn state(&self) -> AmpBarState {
+        self.state
+    }
+
+    pub fn homed(&self) -> bool {
+        matches!(self.state, AmpBarState::Homed)
+    }
+
+    /// revolutions from the stowed hard stop
+    pub fn position(&self) -> f64 {
+        self.motor.get_position().get::<revolution>() - self.zero
+    }
+
+    pub fn stop(&mut self) {
+        self.goal = None;
+        self.motor.stop();
+
+        // a bar that isn't driven looks stalled, start over next time
+        if let AmpBarState::Homing(_) = self.state {
+            self.state = AmpBarState::Unhomed;
+        }
+    }
+
+    /// home again next update
+    pub fn rehome(&mut self) {
+        self.goal = None;
+        self.state = AmpBarState::Unhomed;
+    }
+
+    pub fn stow(&mut self) {
+        self.set_goal(STOWED_POSITION);
+    }
+
+    pub fn deploy(&mut self) {
+        self.set_goal(DEPLOYED_POSITION);
+    }
+
+    pub fn deployed(&self) -> bool {
+        self.position() < (DEPLOYED_POSITION + STOWED_POSITION) / 2.
+    }
+
+    fn set_goal(&mut self, goal: f64) {
+        if self.goal.is_none() {
+            // start the profile from wherever manual control left us
+            self.profile.reset();
+        }
+        self.goal = Some(goal.clamp(SOFT_LIMIT_DEPLOYED, SOFT_LIMIT_STOWED));
+    }
+
+    /// profile finished and the bar is where it was sent
+    pub fn at_goal(&self) -> bool {
+        match self.goal {
+            Some(goal) => {
+                self.homed()
+                    && self.profile.finished()
+                    && (self.position() - goal).abs() < TOLERANCE
+            }
+            None => false,
+        }
+    }
+
+    /// following a profile that hasn't arrived yet
+    pub fn moving(&self) -> bool {
+        self.homed() && self.goal.is_some() && !self.at_goal()
+    }
+
+    /// open loop, still stops at the soft limits once homed. Ignored while
+    /// homing, which needs the bar to itself to find the stop
+    pub fn set(&mut self, value: f64) {
+        if let AmpBarState::Homing(_) = self.state {
+            return;
+        }
+        self.goal = None;
+
+        let position = self.position();
+        let limited = self.homed()
+            && (value > 0. && position >= SOFT_LIMIT_STOWED
+                || value < 0. && position <= SOFT_LIMIT_DEPLOYED);
+
+        if limited {
+            self.motor.set(0.);
+        } else {
+            self.motor.set(sheddable_output(value));
+        }
+    }
+
+    /// run homing and the motion profile, every loop while enabled (see
+    /// `Ferris::lifecycle`)
+    pub fn update(&mut self, dt: &Duration) {
+        match self.state {
+            AmpBarState::Unhomed => {
+                self.state = AmpBarState::Homing(Instant::now());
+                self.motor.set(HOMING_SPEED);
+            }
+            AmpBarState::Homing(started) => {
+                let elapsed = started.elapsed().as_secs_f64();
+                let stalled = elapsed > HOMING_GRACE
+                    && (self.motor.get_current() > HOMING_CURRENT
+                        || self.motor.get_velocity().abs() < HOMING_STALL_VELOCITY);
+
+                if stalled {
+                    self.motor.set(0.);
+                    self.zero = self.motor.get_position().get::<revolution>();
+                    self.state = AmpBarState::Homed;
+                    self.profile.reset();
+                    self.goal = Some(self.goal.unwrap_or(STOWED_POSITION));
+                } else if elapsed > HOMING_TIMEOUT {
+                    self.motor.set(0.);
+                    // fall back to assuming we booted stowed
+                    println!("amp bar failed to home");
+                    self.state = AmpBarState::HomingFailed;
+                }
+            }
+            AmpBarState::Homed | AmpBarState::HomingFailed => {
+                if let Some(goal) = self.goal {
+                    let setpoint = self.profile.step(self.position(), goal, dt);
+                    self.motor
+                        .set_position(Angle::new::<revolution>(setpoint.position + self.zero));
+                }
+            }
+        }
+    }
+}
@@ -1,25 +1,180 @@
+use std::time::{Duration, Instant};
+
+use crate::constants::climber::*;
 use crate::constants::*;
+use crate::profile::{Constraints, TrapezoidProfile};
 use frcrs::rev::MotorType::Brushless;
 use frcrs::rev::Spark;
+use tokio::join;
+use tokio::time::sleep;
+use uom::si::angle::{degree, revolution};
+use uom::si::f64::Angle;
+
+use super::{check_spark, configure_spark, sheddable_output, Alarm};
+
+struct ClimberArm {
+    motor: Spark,
+    /// motor direction that extends the arm
+    sign: f64,
+    /// encoder reading at the bottom hard stop
+    zero: Option<f64>,
+    stalled_since: Option<Instant>,
+    stalled: bool,
+    profile: TrapezoidProfile,
+}
+
+impl ClimberArm {
+    fn new(id: i32, sign: f64) -> Self {
+        let motor = Spark::new(id, Brushless);
+        configure_spark(&motor);
+
+        Self {
+            motor,
+            sign,
+            zero: None,
+            stalled_since: None,
+            stalled: false,
+            profile: TrapezoidProfile::new(Constraints::new(MAX_VELOCITY, MAX_ACCELERATION)),
+        }
+    }
+
+    fn raw_position(&self) -> f64 {
+        self.motor.get_position().get::<revolution>() * self.sign
+    }
+
+    /// revolutions above the bottom, if homed
+    fn extension(&self) -> Option<f64> {
+        self.zero.map(|zero| self.raw_position() - zero)
+    }
+
+    fn stop(&self) {
+        self.motor.stop();
+    }
+
+    /// open loop, drops any profiled move
+    fn set(&mut self, value: f64) {
+        self.profile.reset();
+        self.extend(value);
+    }
+
+    /// profiled move to an extension, true once there
+    fn extend_to(&mut self, goal: f64, dt: &Duration) -> bool {
+        let Some(extension) = self.extension() else {
+            // can't go anywhere without knowing where we are
+            self.set(0.);
+            return false;
+        };
+
+        let setpoint = self.profile.step(extension, goal, dt);
+        let output = (setpoint.position - extension) * KP + setpoint.velocity * KV;
+        self.extend(output.clamp(-1., 1.));
+
+        self.profile.finished() && (extension - goal).abs() < TOLERANCE
+    }
+
+    /// positive extends, limited by the soft limits and stall protection
+    fn extend(&mut self, value: f64) {
+        if value == 0. {
+            // operator let go, give them another try
+            self.stalled = false;
+            self.stalled_since = None;
+            self.motor.set(0.);
+            return;
+        }
+
+        let holding = self.motor.get_current() > STALL_CURRENT
+            && self.motor.get_velocity().abs() < STALL_VELOCITY;
+        match (holding, self.stalled_since) {
+            (true, None) => self.stalled_since = Some(Instant::now()),
+            (true, Some(since)) if since.elapsed().as_secs_f64() > STALL_TIME => {
+                if !self.stalled {
+                    println!("climber {} stalled, cutting power", self.name());
+                }
+                self.stalled = true;
+            }
+            (false, _) => self.stalled_since = None,
+            _ => {}
+        }
+
+        let limited = match self.extension() {
+            Some(extension) => {
+                value > 0. && extension >= SOFT_LIMIT_TOP
+                    || value < 0. && extension <= SOFT_LIMIT_BOTTOM
+            }
+            None => false,
+        };
+
+        if limited || self.stalled {
+            self.motor.set(0.);
+        } else {
+            self.motor.set(sheddable_output(value * self.sign));
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        if self.sign > 0. {
+            "left"
+        } else {
+            "right"
+        }
+    }
+
+    /// retract into the hard stop and zero there
+    async fn home(&mut self) -> bool {
+        let started = Instant::now();
+        self.zero = None;
+        self.motor.set(-HOMING_SPEED * self.sign);
+        sleep(Duration::from_millis(250)).await; // spin up current
+
+        loop {
+            if self.motor.get_current() > HOMING_CURRENT {
+                self.motor.set(0.);
+                self.zero = Some(self.raw_position());
+                return true;
+            }
+            if started.elapsed().as_secs_f64() > HOMING_TIMEOUT {
+                self.motor.set(0.);
+                println!("climber {} failed to home", self.name());
+                return false;
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+    }
+}
 
 pub struct Climber {
-    left: Spark,
-    right: Spark,
+    left: ClimberArm,
+    right: ClimberArm,
 }
 
 impl Climber {
     pub fn new() -> Self {
         Self {
-            left: Spark::new(CLIMBER_LEFT, Brushless),
-            right: Spark::new(CLIMBER_RIGHT, Brushless),
+            left: ClimberArm::new(CLIMBER_LEFT, 1.),
+            right: ClimberArm::new(CLIMBER_RIGHT, -1.),
         }
     }
 
+    pub fn health(&self, alarms: &mut Vec<Alarm>) {
+        check_spark(
+            alarms,
+            "climber left",
+            &self.left.motor,
+            CLIMBER_CURRENT_LIMIT,
+        );
+        check_spark(
+            alarms,
+            "climber right",
+            &self.right.motor,
+            CLIMBER_CURRENT_LIMIT,
+        );
+    }
+
     pub fn stop_left(&self) {
         self.left.stop();
     }
 
-    pub fn stop_right(self) {
+    pub fn stop_right(&self) {
         self.right.stop();
     }
 
@@ -28,16 +183,60 @@ impl Climber {
         self.right.stop();
     }
 
-    pub fn set(&self, value: f64) {
+    /// stop and drop any profiled move, so it doesn't pick back up when enabled
+    pub fn reset(&mut self) {
+        self.stop();
+        self.left.profile.reset();
+        self.right.profile.reset();
+    }
+
+    /// positive climbs (retracts both arms)
+    pub fn set(&mut self, value: f64) {
         self.left.set(-value);
-        self.right.set(value);
+        self.right.set(-value);
     }
 
-    pub fn set_left(&self, value: f64) {
+    /// motor direction, negative retracts
+    pub fn set_left(&mut self, value: f64) {
         self.left.set(value);
     }
 
-    pub fn set_right(&self, value: f64) {
-        self.right.set(value);
+    /// motor direction, positive retracts
+    pub fn set_right(&mut self, value: f64) {
+        self.right.set(-value);
+    }
+
+    pub fn homed(&self) -> bool {
+        self.left.zero.is_some() && self.right.zero.is_some()
+    }
+
+    pub fn stalled(&self) -> bool {
+        self.left.stalled || self.right.stalled
+    }
+
+    /// revolutions above the bottom, (left, right)
+    pub fn extension(&self) -> (Option<f64>, Option<f64>) {
+        (self.left.extension(), self.right.extension())
+    }
+
+    pub async fn home(&mut self) -> bool {
+        let (left, right) = join!(self.left.home(), self.right.home());
+        left && right
+    }
+
+    /// Climb, retracting the arm on the low side faster to level the robot
+    pub fn climb_level(&mut self, roll: Angle) {
+        let correction =
+            (roll.get::<degree>() * LEVEL_KP).clamp(-LEVEL_MAX_CORRECTION, LEVEL_MAX_CORRECTION);
+
+        self.left.set(-(CLIMB_SPEED + correction));
+        self.right.set(-(CLIMB_SPEED - correction));
+    }
+
+    /// Reach both arms up for the chain, true once they're there
+    pub fn raise(&mut self, dt: &Duration) -> bool {
+        let left = self.left.extend_to(READY_EXTENSION, dt);
+        let right = self.right.extend_to(READY_EXTENSION, dt);
+        left && right
     }
 }
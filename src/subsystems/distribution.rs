@@ -0,0 +1,234 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::distribution::*;
+use crate::wrapper;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct Usage {
+    /// joules
+    pub energy: f64,
+    /// amps
+    pub peak_current: f64,
+}
+
+impl Usage {
+    fn record(&mut self, current: f64, voltage: f64, dt: f64) {
+        self.energy += current * voltage * dt;
+        self.peak_current = self.peak_current.max(current);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+struct Channel {
+    channel: usize,
+    subsystem: String,
+    device: String,
+}
+
+/// What each pdh channel feeds, as the robot is actually wired
+///
+/// Read from a deployed file so rewiring doesn't need a rebuild, a list of
+/// `{"channel": 0, "subsystem": "drivetrain", "device": "fr drive"}`. Any
+/// channel not in it counts as "other", named by its number
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct Wiring {
+    channels: Vec<Channel>,
+}
+
+impl Wiring {
+    const PATH: &'static str = "/home/lvuser/deploy/pdh.json";
+
+    /// load from the rio, with nothing mapped if it's missing or unreadable
+    pub fn load() -> Self {
+        let loaded = fs::read_to_string(Self::PATH)
+            .map_err(|e| e.to_string())
+            .and_then(|buf| Self::parse(&buf).map_err(|e| e.to_string()));
+
+        loaded.unwrap_or_else(|e| {
+            println!("no pdh wiring from {}: {e}", Self::PATH);
+            Self::default()
+        })
+    }
+
+    pub fn parse(buf: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(buf)
+    }
+
+    /// subsystem and device on `channel`
+    fn lookup(&self, channel: usize) -> (String, String) {
+        let mapped = self.channels.iter().find(|mapped| mapped.channel == channel);
+        match mapped {
+            Some(mapped) => (mapped.subsystem.clone(), mapped.device.clone()),
+            None => ("other".to_owned(), format!("channel {channel}")),
+        }
+    }
+}
+
+/// Energy and peak current by subsystem and by device, over one match
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct EnergyLog {
+    pub subsystems: BTreeMap<String, Usage>,
+    pub devices: BTreeMap<String, Usage>,
+}
+
+impl EnergyLog {
+    /// `currents` indexed by pdh channel, held for `dt` seconds
+    pub fn record(&mut self, wiring: &Wiring, currents: &[f64], voltage: f64, dt: f64) {
+        // a subsystem's peak is all its channels at once, not its worst one
+        let mut totals: BTreeMap<String, f64> = BTreeMap::new();
+
+        for (channel, &current) in currents.iter().enumerate() {
+            let (subsystem, device) = wiring.lookup(channel);
+            let usage = self.devices.entry(device).or_default();
+            usage.record(current, voltage, dt);
+            *totals.entry(subsystem).or_default() += current;
+        }
+
+        for (subsystem, current) in totals {
+            let usage = self.subsystems.entry(subsystem).or_default();
+            usage.record(current, voltage, dt);
+        }
+    }
+
+    /// joules across every channel
+    pub fn total_energy(&self) -> f64 {
+        self.subsystems.values().map(|usage| usage.energy).sum()
+    }
+}
+
+/// The power distribution hub, with per-match accounting of what drew what
+pub struct Distribution {
+    wiring: Wiring,
+    log: EnergyLog,
+    last_sample: Option<Instant>,
+    enabled: bool,
+}
+
+impl Distribution {
+    pub fn new() -> Self {
+        Self {
+            wiring: Wiring::load(),
+            log: EnergyLog::default(),
+            last_sample: None,
+            enabled: false,
+        }
+    }
+
+    pub fn log(&self) -> &EnergyLog {
+        &self.log
+    }
+
+    /// joules since the last `reset`, as the hub counts it
+    pub fn total_energy(&self) -> Option<f64> {
+        wrapper::pdh_total_energy()
+    }
+
+    /// celsius
+    pub fn temperature(&self) -> Option<f64> {
+        wrapper::pdh_temperature()
+    }
+
+    pub fn total_current(&self) -> Option<f64> {
+        wrapper::pdh_total_current()
+    }
+
+    /// start a new match
+    pub fn reset(&mut self) {
+        wrapper::pdh_reset_total_energy();
+        self.log = EnergyLog::default();
+        self.last_sample = None;
+    }
+
+    /// A match starts when we're enabled in auto, and gets summarized every
+    /// time we're disabled, so auto's numbers print before teleop starts
+    pub fn set_enabled(&mut self, enabled: bool, auto: bool) {
+        if enabled && !self.enabled && auto {
+            self.reset();
+        }
+        if !enabled && self.enabled {
+            self.print_summary();
+        }
+        self.enabled = enabled;
+    }
+
+    /// read every channel if it's been `SAMPLE_PERIOD`, true if it did
+    pub fn sample(&mut self) -> bool {
+        let now = Instant::now();
+        let dt = match self.last_sample {
+            Some(last) if now - last < Duration::from_secs_f64(SAMPLE_PERIOD) => return false,
+            Some(last) => (now - last).as_secs_f64(),
+            // nothing to integrate over yet
+            None => 0.,
+        };
+
+        // a failed read drops this sample, the next one integrates over the gap
+        let (Some(currents), Some(voltage)) = (wrapper::pdh_currents(), wrapper::pdh_voltage())
+        else {
+            return false;
+        };
+        self.last_sample = Some(now);
+        self.log.record(&self.wiring, &currents, voltage, dt);
+
+        true
+    }
+
+    /// print the match summary, biggest draw first
+    pub fn print_summary(&self) {
+        let mut subsystems: Vec<_> = self.log.subsystems.iter().collect();
+        subsystems.sort_by(|a, b| b.1.energy.total_cmp(&a.1.energy));
+
+        let total = self.log.total_energy();
+        match self.total_energy() {
+            Some(measured) => {
+                println!("match energy {total:.0}J ({measured:.0}J measured by the pdh)")
+            }
+            None => println!("match energy {total:.0}J"),
+        }
+        for (subsystem, usage) in subsystems {
+            println!(
+                "  {subsystem}: {:.0}J, peak {:.1}A",
+                usage.energy, usage.peak_current
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EnergyLog, Wiring};
+
+    #[test]
+    fn accounts_by_subsystem() {
+        let wiring = Wiring::parse(
+            r#"[
+                {"channel": 0, "subsystem": "drivetrain", "device": "fr drive"},
+                {"channel": 1, "subsystem": "drivetrain", "device": "fr turn"}
+            ]"#,
+        )
+        .unwrap();
+        let mut log = EnergyLog::default();
+        let mut currents = vec![0.; 24];
+        // fr drive, fr turn, and an unmapped channel
+        currents[0] = 40.;
+        currents[1] = 10.;
+        currents[22] = 2.;
+
+        log.record(&wiring, &currents, 12., 0.5);
+        currents[0] = 20.;
+        log.record(&wiring, &currents, 12., 0.5);
+
+        let drivetrain = log.subsystems["drivetrain"];
+        assert_eq!(drivetrain.peak_current, 50.);
+        assert_eq!(drivetrain.energy, 50. * 6. + 30. * 6.);
+
+        assert_eq!(log.devices["fr drive"].peak_current, 40.);
+        assert_eq!(log.devices["channel 22"].peak_current, 2.);
+        assert_eq!(log.subsystems["other"].energy, 2. * 12.);
+        assert_eq!(log.total_energy(), 480. + 24.);
+    }
+}
@@ -4,9 +4,11 @@ use std::io::{Read, Write};
 use frcrs::ctre::{talon_encoder_tick, CanCoder, ControlMode, Talon};
 
 use crate::constants::drivetrain::SWERVE_ROTATIONS_TO_INCHES;
+use crate::constants::power::{DRIVE_CURRENT_LIMIT, TURN_CURRENT_LIMIT};
 use crate::constants::*;
 use crate::swerve::kinematics::{ModuleState, Swerve};
 use crate::swerve::odometry::{ModuleReturn, Odometry};
+use crate::wrapper;
 use frcrs::navx::NavX;
 use nalgebra::{Rotation2, Vector2};
 use serde::Deserialize;
@@ -15,6 +17,8 @@ use uom::si::angle::{degree, radian, revolution};
 use uom::si::f64::{Angle, Length};
 use uom::si::length::inch;
 
+use super::{check_cancoder, check_navx, check_talon, configure_talon, Alarm};
+
 pub struct Drivetrain {
     navx: NavX,
 
@@ -38,8 +42,12 @@ pub struct Drivetrain {
     pub odometry: Odometry,
 
     pub offset: Angle,
+    /// added to the navx, so the gyro can start at something other than zero
+    angle_seed: Angle,
 
     absolute_offsets: Offsets,
+    /// multiplier on drive output, from `Power::drive_scale`
+    power_scale: f64,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -49,16 +57,26 @@ struct Offsets {
 
 impl Offsets {
     const PATH: &'static str = "/home/lvuser/absolut_homosezual.json";
+    /// zeroed if they've never been written, or can't be read
     fn load() -> Self {
-        let mut file = File::open(Self::PATH).unwrap();
         let mut buf = String::new();
-        file.read_to_string(&mut buf).unwrap();
-        serde_json::from_str(&buf).unwrap_or(Self { offsets: [0.; 4] })
+        let loaded = File::open(Self::PATH)
+            .and_then(|mut file| file.read_to_string(&mut buf))
+            .ok()
+            .and_then(|_| serde_json::from_str(&buf).ok());
+
+        loaded.unwrap_or_else(|| {
+            println!("absolute offsets not found at {}, using zeros", Self::PATH);
+            Self { offsets: [0.; 4] }
+        })
     }
     fn store(&self) {
-        let mut file = File::create(Self::PATH).unwrap();
-        let buf = serde_json::to_string(&self).unwrap();
-        file.write_all(buf.as_bytes()).unwrap();
+        let stored = serde_json::to_string(&self)
+            .map_err(std::io::Error::from)
+            .and_then(|buf| File::create(Self::PATH)?.write_all(buf.as_bytes()));
+        if let Err(e) = stored {
+            println!("couldn't store absolute offsets: {e}");
+        }
     }
 }
 
@@ -75,6 +93,28 @@ impl Drivetrain {
         let bl_turn = Talon::new(BL_TURN, Some("can0".to_owned()));
         let br_turn = Talon::new(BR_TURN, Some("can0".to_owned()));
 
+        let fr_drive = Talon::new(FR_DRIVE, Some("can0".to_owned()));
+        let fl_drive = Talon::new(FL_DRIVE, Some("can0".to_owned()));
+        let bl_drive = Talon::new(BL_DRIVE, Some("can0".to_owned()));
+        let br_drive = Talon::new(BR_DRIVE, Some("can0".to_owned()));
+
+        for (drive, id) in [
+            (&fr_drive, FR_DRIVE),
+            (&fl_drive, FL_DRIVE),
+            (&bl_drive, BL_DRIVE),
+            (&br_drive, BR_DRIVE),
+        ] {
+            configure_talon(drive, id, DRIVE_CURRENT_LIMIT);
+        }
+        for (turn, id) in [
+            (&fr_turn, FR_TURN),
+            (&fl_turn, FL_TURN),
+            (&bl_turn, BL_TURN),
+            (&br_turn, BR_TURN),
+        ] {
+            configure_talon(turn, id, TURN_CURRENT_LIMIT);
+        }
+
         for (encoder, offset) in [&fr_encoder, &fl_encoder, &bl_encoder, &br_encoder]
             .iter()
             .zip(absolute_offsets.offsets.iter_mut())
@@ -94,19 +134,19 @@ impl Drivetrain {
         let dt = Self {
             navx: NavX::new(),
 
-            fr_drive: Talon::new(FR_DRIVE, Some("can0".to_owned())),
+            fr_drive,
             fr_turn,
             fr_encoder,
 
-            fl_drive: Talon::new(FL_DRIVE, Some("can0".to_owned())),
+            fl_drive,
             fl_turn,
             fl_encoder,
 
-            bl_drive: Talon::new(BL_DRIVE, Some("can0".to_owned())),
+            bl_drive,
             bl_turn,
             bl_encoder,
 
-            br_drive: Talon::new(BR_DRIVE, Some("can0".to_owned())),
+            br_drive,
             br_turn,
             br_encoder,
 
@@ -114,13 +154,35 @@ impl Drivetrain {
             odometry: Odometry::new(),
 
             offset: Angle::new::<degree>(0.),
+            angle_seed: Angle::new::<degree>(0.),
 
             absolute_offsets,
+            power_scale: 1.,
         };
 
         dt
     }
 
+    pub fn health(&self, alarms: &mut Vec<Alarm>) {
+        check_navx(alarms, "navx");
+
+        check_talon(alarms, "fr drive", FR_DRIVE);
+        check_talon(alarms, "fr turn", FR_TURN);
+        check_cancoder(alarms, "fr encoder", FR_ENCODER);
+
+        check_talon(alarms, "fl drive", FL_DRIVE);
+        check_talon(alarms, "fl turn", FL_TURN);
+        check_cancoder(alarms, "fl encoder", FL_ENCODER);
+
+        check_talon(alarms, "bl drive", BL_DRIVE);
+        check_talon(alarms, "bl turn", BL_TURN);
+        check_cancoder(alarms, "bl encoder", BL_ENCODER);
+
+        check_talon(alarms, "br drive", BR_DRIVE);
+        check_talon(alarms, "br turn", BR_TURN);
+        check_cancoder(alarms, "br encoder", BR_ENCODER);
+    }
+
     pub fn write_absolute(&mut self) {
         let mut offsets = Offsets::load();
         for (encoder, offset) in [
@@ -138,6 +200,11 @@ impl Drivetrain {
         offsets.store();
     }
 
+    /// scale drive output for battery voltage and load shedding
+    pub fn set_power_scale(&mut self, scale: f64) {
+        self.power_scale = scale;
+    }
+
     pub fn stop(&self) {
         self.fr_drive.stop();
         self.fr_turn.stop();
@@ -221,14 +288,16 @@ impl Drivetrain {
             })
             .collect();
 
+        let scaled = |speed: f64| (speed * self.power_scale).clamp(-1., 1.);
+
         self.fr_drive
-            .set(ControlMode::Percent, wheel_speeds[0].speed);
+            .set(ControlMode::Percent, scaled(wheel_speeds[0].speed));
         self.fl_drive
-            .set(ControlMode::Percent, wheel_speeds[1].speed);
+            .set(ControlMode::Percent, scaled(wheel_speeds[1].speed));
         self.bl_drive
-            .set(ControlMode::Percent, wheel_speeds[2].speed);
+            .set(ControlMode::Percent, scaled(wheel_speeds[2].speed));
         self.br_drive
-            .set(ControlMode::Percent, wheel_speeds[3].speed);
+            .set(ControlMode::Percent, scaled(wheel_speeds[3].speed));
 
         self.fr_turn.set(
             ControlMode::Position,
@@ -276,7 +345,12 @@ impl Drivetrain {
     }
 
     pub fn get_angle(&self) -> Angle {
-        Angle::new::<degree>(self.navx.get_angle())
+        Angle::new::<degree>(self.navx.get_angle()) + self.angle_seed
+    }
+
+    /// side to side tilt, none if the navx couldn't be read
+    pub fn get_roll(&self) -> Option<Angle> {
+        wrapper::navx_roll().map(Angle::new::<degree>)
     }
 
     pub fn get_offset(&self) -> Angle {
@@ -290,11 +364,19 @@ impl Drivetrain {
         Angle::new::<degree>(difference)
     }
 
-    pub fn reset_angle(&self) {
-        self.navx.reset_angle()
+    pub fn reset_angle(&mut self) {
+        self.navx.reset_angle();
+        self.angle_seed = Angle::new::<degree>(0.);
     }
 
     pub fn reset_heading(&mut self) {
         self.offset = self.get_angle();
     }
+
+    /// make the gyro read `angle` here, with field forward as the heading
+    pub fn seed_angle(&mut self, angle: Angle) {
+        self.navx.reset_angle();
+        self.angle_seed = angle;
+        self.offset = Angle::new::<degree>(0.);
+    }
 }
@@ -0,0 +1,229 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use frcrs::rev::Spark;
+use serde::Serialize;
+
+use crate::constants::health::*;
+use crate::constants::power::CURRENT_LIMIT_MARGIN;
+use crate::wrapper;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum Severity {
+    Warning,
+    Error,
+    /// the mechanism can't be trusted at all
+    Critical,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Alarm {
+    pub device: String,
+    pub problem: &'static str,
+    pub severity: Severity,
+    /// reading that raised it, if there is one
+    pub value: Option<f64>,
+}
+
+impl Alarm {
+    pub fn new(device: &str, problem: &'static str, severity: Severity) -> Self {
+        Self {
+            device: device.to_owned(),
+            problem,
+            severity,
+            value: None,
+        }
+    }
+
+    pub fn with_value(self, value: f64) -> Self {
+        Self {
+            value: Some(value),
+            ..self
+        }
+    }
+
+    /// same device, problem and severity, ignoring the reading
+    fn same(&self, other: &Alarm) -> bool {
+        self.device == other.device
+            && self.problem == other.problem
+            && self.severity == other.severity
+    }
+}
+
+fn check_motor(alarms: &mut Vec<Alarm>, name: &str, temperature: f64, current: f64) {
+    if temperature > MOTOR_TEMPERATURE_CRITICAL {
+        alarms.push(Alarm::new(name, "overheating", Severity::Critical).with_value(temperature));
+    } else if temperature > MOTOR_TEMPERATURE_WARNING {
+        alarms.push(Alarm::new(name, "hot", Severity::Warning).with_value(temperature));
+    }
+
+    if current > MOTOR_CURRENT_WARNING {
+        alarms.push(Alarm::new(name, "high current", Severity::Warning).with_value(current));
+    }
+}
+
+/// `current_limit` is the one burned into it, see `configure_spark`
+pub fn check_spark(alarms: &mut Vec<Alarm>, name: &str, spark: &Spark, current_limit: f64) {
+    // any error talking to it means it's off the bus or its firmware is unhappy
+    let error = spark.get_last_error();
+    if error != 0 {
+        let alarm = Alarm::new(name, "not responding", Severity::Critical);
+        alarms.push(alarm.with_value(error as f64));
+        return;
+    }
+
+    let faults = spark.get_faults();
+    if faults != 0 {
+        alarms.push(Alarm::new(name, "faulted", Severity::Error).with_value(faults as f64));
+    }
+
+    let current = spark.get_current();
+    if current > current_limit + CURRENT_LIMIT_MARGIN {
+        alarms.push(Alarm::new(name, "current limit not set", Severity::Error).with_value(current));
+    }
+
+    check_motor(alarms, name, spark.get_temperature(), current);
+}
+
+/// talons and cancoders are read by CAN id, frcrs doesn't expose their status
+pub fn check_talon(alarms: &mut Vec<Alarm>, name: &str, id: i32) {
+    if !wrapper::talon_connected(id) {
+        alarms.push(Alarm::new(name, "not responding", Severity::Critical));
+        return;
+    }
+
+    let faults = wrapper::talon_faults(id).unwrap_or(0);
+    if faults != 0 {
+        alarms.push(Alarm::new(name, "faulted", Severity::Error).with_value(faults as f64));
+    }
+
+    let temperature = wrapper::talon_temperature(id).unwrap_or(0.);
+    let current = wrapper::talon_current(id).unwrap_or(0.);
+    check_motor(alarms, name, temperature, current);
+}
+
+pub fn check_cancoder(alarms: &mut Vec<Alarm>, name: &str, id: i32) {
+    if !wrapper::cancoder_connected(id) {
+        alarms.push(Alarm::new(name, "not responding", Severity::Critical));
+        return;
+    }
+
+    let faults = wrapper::cancoder_faults(id).unwrap_or(0);
+    if faults != 0 {
+        alarms.push(Alarm::new(name, "faulted", Severity::Error).with_value(faults as f64));
+    }
+}
+
+pub fn check_navx(alarms: &mut Vec<Alarm>, name: &str) {
+    if !wrapper::navx_connected() {
+        alarms.push(Alarm::new(name, "not responding", Severity::Critical));
+    }
+}
+
+/// Alarms from every subsystem, polled every `POLL_PERIOD`
+#[derive(Default)]
+pub struct Health {
+    /// by subsystem, so a busy subsystem keeps its last report
+    alarms: BTreeMap<&'static str, Vec<Alarm>>,
+    last_poll: Option<Instant>,
+}
+
+impl Health {
+    /// time to poll the devices again
+    pub fn due(&mut self) -> bool {
+        let due = self.last_poll.map_or(true, |last| {
+            last.elapsed() >= Duration::from_secs_f64(POLL_PERIOD)
+        });
+        if due {
+            self.last_poll = Some(Instant::now());
+        }
+        due
+    }
+
+    /// replace a subsystem's alarms, logging any that were raised or cleared
+    pub fn report(&mut self, subsystem: &'static str, alarms: Vec<Alarm>) {
+        let previous = self.alarms.remove(subsystem).unwrap_or_default();
+
+        for alarm in &alarms {
+            if !previous.iter().any(|old| old.same(alarm)) {
+                println!(
+                    "{:?}: {} {} ({subsystem})",
+                    alarm.severity, alarm.device, alarm.problem
+                );
+            }
+        }
+        for alarm in &previous {
+            if !alarms.iter().any(|new| new.same(alarm)) {
+                println!("cleared: {} {} ({subsystem})", alarm.device, alarm.problem);
+            }
+        }
+
+        self.alarms.insert(subsystem, alarms);
+    }
+
+    /// every active alarm, worst first
+    pub fn alarms(&self) -> Vec<Alarm> {
+        let mut alarms: Vec<Alarm> = self.alarms.values().flatten().cloned().collect();
+        alarms.sort_by(|a, b| b.severity.cmp(&a.severity));
+        alarms
+    }
+
+    pub fn worst(&self) -> Option<Severity> {
+        self.alarms
+            .values()
+            .flatten()
+            .map(|alarm| alarm.severity)
+            .max()
+    }
+
+    /// nothing worse than a warning
+    pub fn healthy(&self) -> bool {
+        self.worst().map_or(true, |worst| worst < Severity::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Alarm, Health, Severity};
+
+    #[test]
+    fn worst_first() {
+        let mut health = Health::default();
+        health.report(
+            "intake",
+            vec![
+                Alarm::new("intake roller", "hot", Severity::Warning),
+                Alarm::new("intake actuate", "not responding", Severity::Critical),
+            ],
+        );
+        health.report(
+            "shooter",
+            vec![Alarm::new("feeder", "faulted", Severity::Error)],
+        );
+
+        let severities: Vec<Severity> = health.alarms().iter().map(|a| a.severity).collect();
+        assert_eq!(
+            severities,
+            vec![Severity::Critical, Severity::Error, Severity::Warning]
+        );
+        assert!(!health.healthy());
+    }
+
+    #[test]
+    fn reports_replace_per_subsystem() {
+        let mut health = Health::default();
+        health.report(
+            "intake",
+            vec![Alarm::new("roller", "faulted", Severity::Error)],
+        );
+        health.report(
+            "shooter",
+            vec![Alarm::new("feeder", "hot", Severity::Warning)],
+        );
+        health.report("intake", vec![]);
+
+        assert_eq!(health.alarms().len(), 1);
+        assert_eq!(health.worst(), Some(Severity::Warning));
+        assert!(health.healthy());
+    }
+}
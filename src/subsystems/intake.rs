@@ -1,6 +1,17 @@
-use std::{sync::atomic::{AtomicI64, Ordering}, time::Duration};
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
-use crate::{constants::*, subsystems::intake::intake::{INTAKE_DOWN_GOAL, INTAKE_UP_GOAL}};
+use crate::{
+    constants::{
+        power::{INTAKE_ACTUATE_CURRENT_LIMIT, INTAKE_ROLLER_CURRENT_LIMIT, NOMINAL_VOLTAGE},
+        *,
+    },
+    feedforward::ArmFeedforward,
+    profile::{Constraints, TrapezoidProfile},
+};
 use frcrs::{
     dio::DIO,
     rev::{MotorType, Spark},
@@ -8,34 +19,149 @@ use frcrs::{
 use tokio::time::sleep;
 use uom::si::{angle::degree, f64::Angle};
 
-use self::intake::{INTAKE_DEGREES_PER_SECOND, INTAKE_ZERO_POINT};
+use self::intake::*;
+use super::{
+    check_spark, configure_spark, sheddable_output, spark_output, wait, wait_for, Alarm, Outcome,
+    Sequence, Severity,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntakeState {
+    Unhomed,
+    Homing,
+    Homed,
+    HomingFailed,
+}
+
+/// The actuators and their position loop
+///
+/// The loop is closed here rather than on the sparks, so `hold` has to run
+/// every loop while enabled. Sequences hold the `Intake` across awaits, so
+/// this is shared separately for `Ferris::lifecycle` to reach. Nothing
+/// borrows it across an await
+pub struct Pivot {
+    left: Spark,
+    right: Spark,
+
+    zero: Angle,
+    /// degrees
+    profile: TrapezoidProfile,
+    feedforward: ArmFeedforward,
+    /// last position sent to the actuators
+    setpoint: Option<Angle>,
+}
 
 pub struct Intake {
     left_roller: Spark,
     right_roller: Spark,
 
-    left_actuate: Spark,
-    right_actuate: Spark,
+    pivot: Rc<RefCell<Pivot>>,
 
     limit: DIO,
     cam_limit: DIO,
 
-    actuate_zero: Angle,
+    state: IntakeState,
+    /// a position command was dropped because we weren't homed
+    refused: bool,
 }
 
 const COUNTS_PER_REVOLUTION: f64 = 41.6;
 
+impl Pivot {
+    fn new() -> Self {
+        let left = Spark::new(INTAKE_ACTUATE_LEFT, MotorType::Brushless);
+        let right = Spark::new(INTAKE_ACTUATE_RIGHT, MotorType::Brushless);
+        configure_spark(&left);
+        configure_spark(&right);
+
+        Self {
+            left,
+            right,
+
+            zero: Angle::new::<degree>(0.),
+            profile: TrapezoidProfile::new(Constraints::new(
+                INTAKE_DEGREES_PER_SECOND,
+                INTAKE_DEGREES_PER_SECOND_SQUARED,
+            )),
+            feedforward: ArmFeedforward::new(INTAKE_KS, INTAKE_KG, INTAKE_KV, INTAKE_KA),
+            setpoint: None,
+        }
+    }
+
+    fn stop(&mut self) {
+        self.profile.reset();
+        self.setpoint = None;
+        self.left.stop();
+        self.right.stop();
+    }
+
+    /// open loop, dropping any setpoint
+    fn set(&mut self, value: f64) {
+        self.profile.reset();
+        self.setpoint = None;
+        self.drive(value);
+    }
+
+    /// mirrored across the robot, so the right side spins the other way
+    fn drive(&self, value: f64) {
+        let value = spark_output(value);
+        self.left.set(value);
+        self.right.set(-value);
+    }
+
+    fn position(&self) -> Angle {
+        (self.left.get_position() - self.zero) / COUNTS_PER_REVOLUTION
+    }
+
+    /// degrees per second
+    fn velocity(&self) -> f64 {
+        // rpm of the motor, 6 degrees per second each
+        self.left.get_velocity() * 6. / COUNTS_PER_REVOLUTION
+    }
+
+    fn current(&self) -> f64 {
+        self.left.get_current()
+    }
+
+    /// the current position is `point` degrees
+    fn set_zero(&mut self, point: f64) {
+        self.zero = self.left.get_position() - Angle::new::<degree>(point) * COUNTS_PER_REVOLUTION;
+        self.profile.reset();
+    }
+
+    /// Drive towards the last setpoint again, if under position control.
+    /// Every loop while enabled, see `Ferris::lifecycle`
+    pub fn hold(&mut self) {
+        if let Some(setpoint) = self.setpoint {
+            self.set_position(setpoint, 0., 0.);
+        }
+    }
+
+    /// PD on the measured position, with gravity and motion feedforward on
+    /// top, sent as a fraction of `NOMINAL_VOLTAGE`
+    ///
+    /// velocity and acceleration are per second (squared) in radians
+    fn set_position(&mut self, angle: Angle, velocity: f64, acceleration: f64) {
+        let from_horizontal = angle - Angle::new::<degree>(INTAKE_HORIZONTAL);
+        let feedforward = self
+            .feedforward
+            .calculate(from_horizontal, velocity, acceleration);
+
+        let error = (angle - self.position()).get::<degree>();
+        let velocity_error = velocity.to_degrees() - self.velocity();
+        let volts = feedforward + INTAKE_KP * error + INTAKE_KD * velocity_error;
+
+        self.setpoint = Some(angle);
+        self.drive((volts / NOMINAL_VOLTAGE).clamp(-1., 1.));
+    }
+}
+
 impl Intake {
     pub fn new() -> Self {
         let left_roller = Spark::new(INTAKE_ROLLER_LEFT, MotorType::Brushless);
         let right_roller = Spark::new(INTAKE_ROLLER_RIGHT, MotorType::Brushless);
-
-        let mut left_actuate = Spark::new(INTAKE_ACTUATE_LEFT, MotorType::Brushless);
-        let pid = left_actuate.get_pid();
-        pid.set_p(0.08);
-        pid.set_d(0.45);
-
-        let right_actuate = Spark::new(INTAKE_ACTUATE_RIGHT, MotorType::Brushless);
+        configure_spark(&left_roller);
+        configure_spark(&right_roller);
 
         let limit = DIO::new(INTAKE_LIMIT);
         let cam_limit = DIO::new(INTAKE_CAM_LIMIT);
@@ -44,27 +170,64 @@ impl Intake {
             left_roller,
             right_roller,
 
-            left_actuate,
-            right_actuate,
+            pivot: Rc::new(RefCell::new(Pivot::new())),
 
             limit,
             cam_limit,
 
-            actuate_zero: Angle::new::<degree>(0.),
+            state: IntakeState::Unhomed,
+            refused: false,
         }
     }
 
-    pub fn stop(&self) {
+    pub fn stop(&mut self) {
         self.left_roller.stop();
         //self.right_roller.stop();
 
-        self.left_actuate.stop();
-        //self.right_actuate.stop();
+        self.stop_actuate();
+    }
+
+    pub fn stop_actuate(&mut self) {
+        self.pivot.borrow_mut().stop();
     }
 
-    pub fn stop_actuate(&self) {
-        self.left_actuate.stop();
-        //self.right_actuate.stop();
+    /// for keeping the position loop running while something holds the intake
+    pub fn pivot(&self) -> Rc<RefCell<Pivot>> {
+        self.pivot.clone()
+    }
+
+    pub fn health(&self, alarms: &mut Vec<Alarm>) {
+        check_spark(
+            alarms,
+            "intake left roller",
+            &self.left_roller,
+            INTAKE_ROLLER_CURRENT_LIMIT,
+        );
+        check_spark(
+            alarms,
+            "intake right roller",
+            &self.right_roller,
+            INTAKE_ROLLER_CURRENT_LIMIT,
+        );
+        let pivot = self.pivot.borrow();
+        check_spark(
+            alarms,
+            "intake left actuate",
+            &pivot.left,
+            INTAKE_ACTUATE_CURRENT_LIMIT,
+        );
+        check_spark(
+            alarms,
+            "intake right actuate",
+            &pivot.right,
+            INTAKE_ACTUATE_CURRENT_LIMIT,
+        );
+
+        // position commands are refused until someone zeroes it again
+        if self.state == IntakeState::HomingFailed {
+            let alarm = Alarm::new("intake actuate", "failed to home", Severity::Error);
+            alarms.push(alarm);
+        }
     }
 
     pub fn stop_rollers(&self) {
@@ -73,19 +236,22 @@ impl Intake {
     }
 
     pub fn set_rollers(&self, value: f64) {
-        self.left_roller.set(value);
+        self.left_roller.set(sheddable_output(value));
         //self.right_roller.set(value);
     }
 
-    pub fn set_actuate(&self, value: f64) {
-        self.left_actuate.set(value);
-        //self.right_actuate.set(value);
+    pub fn set_actuate(&mut self, value: f64) {
+        self.pivot.borrow_mut().set(value);
     }
 
     pub fn roller_current(&mut self) -> f64 {
         self.left_roller.get_current()
     }
 
+    pub fn roller_velocity(&mut self) -> f64 {
+        self.left_roller.get_velocity()
+    }
+
     pub fn stalled(&mut self) -> bool {
         self.left_roller.get_current() > intake::INTAKE_OCCUPIED_CURRENT
             && self.left_roller.get_velocity() < intake::INTAKE_OCCUPIED_VELOCITY
@@ -106,80 +272,167 @@ impl Intake {
     }
 
     pub fn actuate_position(&mut self) -> Angle {
-        (self.left_actuate.get_position() - self.actuate_zero) / COUNTS_PER_REVOLUTION
+        self.pivot.borrow().position()
+    }
+
+    /// where the actuators were last sent, if under position control
+    pub fn actuate_setpoint(&self) -> Option<Angle> {
+        self.pivot.borrow().setpoint
     }
 
     pub fn constrained(&self) -> bool {
         self.at_limit()
     }
 
-    pub async fn grab(&mut self) {
-        self.set_rollers(0.6);
-        wait(|| self.running()).await;
-        wait(|| self.stalled()).await;
-        self.stop_rollers();
+    /// run the rollers until a note stalls them
+    pub async fn grab(&mut self) -> Outcome {
+        let mut sequence = Sequence::new("grab", self, |intake| intake.stop_rollers());
+        let intake = &mut *sequence;
+
+        let outcome = async {
+            intake.set_rollers(0.6);
+            wait_for(
+                || intake.running(),
+                Duration::from_secs_f64(INTAKE_SPIN_UP_TIMEOUT),
+            )
+            .await?;
+            wait(|| intake.stalled()).await;
+            Ok(())
+        }
+        .await;
+
+        sequence.finish(outcome)
+    }
+
+    pub fn state(&self) -> IntakeState {
+        self.state
     }
 
+    pub fn homed(&self) -> bool {
+        matches!(self.state, IntakeState::Homed)
+    }
+
+    /// a position command was refused since the last successful home
+    pub fn refused(&self) -> bool {
+        self.refused
+    }
+
+    /// Find the stowed limit switch and zero there
+    ///
+    /// Falls back to zeroing on the hard stop if the switch never trips,
+    /// gives up after `INTAKE_HOMING_TIMEOUT`. How it went is in `state`, and
+    /// a failure raises an alarm through `health`
     pub async fn zero(&mut self) {
-        self.set_actuate(0.3);
-        wait(|| self.at_limit()).await;
+        let started = Instant::now();
+        self.state = IntakeState::Homing;
+
+        self.set_actuate(INTAKE_HOMING_SPEED);
+        sleep(Duration::from_millis(250)).await; // spin up current
+
+        loop {
+            if self.at_limit() {
+                break;
+            }
+            if self.pivot.borrow().current() > INTAKE_HOMING_CURRENT {
+                self.set_actuate(0.);
+                println!("intake limit switch never tripped, zeroing on the hard stop");
+                self.set_zero(INTAKE_HARD_STOP_POINT);
+                return;
+            }
+            if started.elapsed().as_secs_f64() > INTAKE_HOMING_TIMEOUT {
+                return self.homing_failed();
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+
         self.set_actuate(0.);
         sleep(Duration::from_millis(750)).await;
-        wait(|| self.at_limit()).await;
-        self.set_actuate(-0.15);
-        wait(|| !self.at_limit()).await;
+        if !self.wait_homing(started, |intake| intake.at_limit()).await {
+            return self.homing_failed();
+        }
+
+        // back off until the switch just releases
+        self.set_actuate(-INTAKE_BACKOFF_SPEED);
+        if !self.wait_homing(started, |intake| !intake.at_limit()).await {
+            return self.homing_failed();
+        }
         self.set_actuate(0.);
-        self.actuate_zero =
-            self.left_actuate.get_position() - Angle::new::<degree>(INTAKE_ZERO_POINT);
-    }
 
-    /// 0deg is stowed
-    /// 180deg is out
-    pub fn actuate_to(&mut self, angle: Angle) {
-        self.left_actuate
-            .set_position(angle * COUNTS_PER_REVOLUTION + self.actuate_zero)
+        self.set_zero(INTAKE_ZERO_POINT);
     }
 
+    /// false if homing ran out of time first
+    async fn wait_homing<F>(&self, started: Instant, condition: F) -> bool
+    where
+        F: Fn(&Self) -> bool,
+    {
+        loop {
+            if condition(self) {
+                return true;
+            }
+            if started.elapsed().as_secs_f64() > INTAKE_HOMING_TIMEOUT {
+                return false;
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+    }
 
-    /// 0deg is stowed
-    /// 180deg is out
-    pub fn actuate_to_trapezoid_rdy(&mut self, angle: Angle, dt: &Duration) -> bool {
-        // bodge
-        static ANGLE: AtomicI64 = AtomicI64::new((0) as i64 * 100);
-
-        let current = ANGLE.load(Ordering::SeqCst) as f64 / 100.;
-        let max = dt.as_secs_f64() * INTAKE_DEGREES_PER_SECOND * 6.;
-        let goal = angle.get::<degree>();
-
-        let compromise = if goal > current {
-            goal.min(current+max)
-        } else {
-            goal.max(current-max)
-        };
+    /// the current position is `point` degrees
+    fn set_zero(&mut self, point: f64) {
+        self.pivot.borrow_mut().set_zero(point);
+        self.state = IntakeState::Homed;
+        self.refused = false;
+    }
 
-        ANGLE.store((compromise * 100.) as i64, Ordering::SeqCst);
+    fn homing_failed(&mut self) {
+        self.set_actuate(0.);
+        println!("intake failed to home");
+        self.state = IntakeState::HomingFailed;
+    }
 
-        let compromise = Angle::new::<degree>(compromise);
-        self.left_actuate.set_position(compromise * COUNTS_PER_REVOLUTION + self.actuate_zero);
+    /// drop a position command, true if it can't run
+    fn refuse(&mut self) -> bool {
+        if self.homed() {
+            return false;
+        }
 
-        goal == current
+        if !self.refused {
+            println!("intake isn't homed, refusing to move it");
+        }
+        self.refused = true;
+        self.stop_actuate();
+        true
     }
 
     /// 0deg is stowed
     /// 180deg is out
-    pub fn actuate_to_trapezoid(&mut self, angle: Angle, dt: &Duration) {
-        self.actuate_to_trapezoid_rdy(angle, dt);
+    pub fn actuate_to(&mut self, angle: Angle) {
+        if self.refuse() {
+            return;
+        }
+        let mut pivot = self.pivot.borrow_mut();
+        pivot.profile.reset();
+        pivot.set_position(angle, 0., 0.);
     }
-}
 
-pub async fn wait<F>(mut condition: F)
-where
-    F: FnMut() -> bool,
-{
-    loop {
-        if condition() {
-            return;
-        };
-        sleep(Duration::from_millis(20)).await;
+    /// 0deg is stowed
+    /// 180deg is out
+    /// step the motion profile towards angle, true once the profile has arrived
+    pub fn actuate_profiled(&mut self, angle: Angle, dt: &Duration) -> bool {
+        if self.refuse() {
+            return false;
+        }
+
+        let mut pivot = self.pivot.borrow_mut();
+        let measured = pivot.position().get::<degree>();
+        let setpoint = pivot.profile.step(measured, angle.get::<degree>(), dt);
+
+        pivot.set_position(
+            Angle::new::<degree>(setpoint.position),
+            setpoint.velocity.to_radians(),
+            setpoint.acceleration.to_radians(),
+        );
+
+        pivot.profile.finished()
     }
 }
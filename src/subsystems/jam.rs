@@ -0,0 +1,82 @@
+use std::time::{Duration, Instant};
+
+use crate::constants::jam::*;
+
+/// Flags a motor that's pulling current without turning for too long
+pub struct JamDetector {
+    /// amps
+    current: f64,
+    /// rpm, slower than this counts as stopped
+    velocity: f64,
+    time: Duration,
+    since: Option<Instant>,
+}
+
+impl JamDetector {
+    pub fn new(current: f64, velocity: f64, time: Duration) -> Self {
+        Self {
+            current,
+            velocity,
+            time,
+            since: None,
+        }
+    }
+
+    pub fn feeder() -> Self {
+        Self::new(
+            FEEDER_JAM_CURRENT,
+            FEEDER_JAM_VELOCITY,
+            Duration::from_secs_f64(JAM_TIME),
+        )
+    }
+
+    pub fn rollers() -> Self {
+        Self::new(
+            ROLLER_JAM_CURRENT,
+            ROLLER_JAM_VELOCITY,
+            Duration::from_secs_f64(JAM_TIME),
+        )
+    }
+
+    pub fn update(&mut self, current: f64, velocity: f64) -> bool {
+        self.update_at(current, velocity, Instant::now())
+    }
+
+    pub fn update_at(&mut self, current: f64, velocity: f64, now: Instant) -> bool {
+        if current < self.current || velocity.abs() > self.velocity {
+            self.since = None;
+            return false;
+        }
+
+        let since = *self.since.get_or_insert(now);
+        now.duration_since(since) >= self.time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::JamDetector;
+
+    #[test]
+    fn needs_to_stay_stuck() {
+        let start = Instant::now();
+        let mut jam = JamDetector::new(20., 100., Duration::from_millis(300));
+
+        assert!(!jam.update_at(40., 0., start));
+        assert!(!jam.update_at(40., 0., start + Duration::from_millis(200)));
+        assert!(jam.update_at(40., 0., start + Duration::from_millis(300)));
+    }
+
+    #[test]
+    fn spinning_resets() {
+        let start = Instant::now();
+        let mut jam = JamDetector::new(20., 100., Duration::from_millis(300));
+
+        assert!(!jam.update_at(40., 0., start));
+        assert!(!jam.update_at(40., -2000., start + Duration::from_millis(200)));
+        assert!(!jam.update_at(40., 0., start + Duration::from_millis(400)));
+        assert!(!jam.update_at(5., 0., start + Duration::from_millis(800)));
+    }
+}
@@ -1,9 +1,25 @@
+mod amp;
 mod climber;
+mod distribution;
 mod drivetrain;
+mod health;
 mod intake;
+mod jam;
+mod note;
+mod parking;
+mod power;
+mod sequence;
 mod shooter;
 
+pub use amp::*;
 pub use climber::*;
+pub use distribution::*;
 pub use drivetrain::*;
+pub use health::*;
 pub use intake::*;
+pub use jam::*;
+pub use note::*;
+pub use parking::*;
+pub use power::*;
+pub use sequence::*;
 pub use shooter::*;
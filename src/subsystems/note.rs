@@ -0,0 +1,294 @@
+use std::time::{Duration, Instant};
+
+use crate::constants::note::*;
+
+/// Only changes once the raw signal has held its new value for `delay`
+pub struct Debouncer {
+    delay: Duration,
+    value: bool,
+    changed_since: Option<Instant>,
+}
+
+impl Debouncer {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            value: false,
+            changed_since: None,
+        }
+    }
+
+    pub fn value(&self) -> bool {
+        self.value
+    }
+
+    pub fn update(&mut self, raw: bool, now: Instant) -> bool {
+        if raw == self.value {
+            self.changed_since = None;
+            return self.value;
+        }
+
+        let since = *self.changed_since.get_or_insert(now);
+        if now.duration_since(since) >= self.delay {
+            self.value = raw;
+            self.changed_since = None;
+        }
+
+        self.value
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NoteLocation {
+    None,
+    /// held in the intake rollers
+    Intake,
+    /// on its way from the intake to the shooter
+    Transit,
+    /// sitting on the beam break, ready to shoot
+    Staged,
+}
+
+impl NoteLocation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            NoteLocation::None => "none",
+            NoteLocation::Intake => "intake",
+            NoteLocation::Transit => "transit",
+            NoteLocation::Staged => "staged",
+        }
+    }
+}
+
+/// Raw readings for one loop, `None` if the subsystem was busy
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoteReadings {
+    pub intake_stalled: Option<bool>,
+    pub cam_limit: Option<bool>,
+    pub beam_break: Option<bool>,
+}
+
+/// Fuses the intake and shooter sensors into where we think the note is
+pub struct NoteSensor {
+    stalled: Debouncer,
+    cam: Debouncer,
+    beam: Debouncer,
+
+    location: NoteLocation,
+    /// 0 to 1
+    confidence: f64,
+    /// when the note left the intake sensors without reaching the beam break
+    transit_since: Option<Instant>,
+    disagreement: Option<&'static str>,
+}
+
+impl Default for NoteSensor {
+    fn default() -> Self {
+        Self {
+            stalled: Debouncer::new(Duration::from_secs_f64(STALL_DEBOUNCE)),
+            cam: Debouncer::new(Duration::from_secs_f64(CAM_DEBOUNCE)),
+            beam: Debouncer::new(Duration::from_secs_f64(BEAM_DEBOUNCE)),
+
+            location: NoteLocation::None,
+            confidence: 1.,
+            transit_since: None,
+            disagreement: None,
+        }
+    }
+}
+
+impl NoteSensor {
+    pub fn location(&self) -> NoteLocation {
+        self.location
+    }
+
+    pub fn confidence(&self) -> f64 {
+        self.confidence
+    }
+
+    /// sensors currently contradicting each other, if any
+    pub fn disagreement(&self) -> Option<&'static str> {
+        self.disagreement
+    }
+
+    pub fn staged(&self) -> bool {
+        self.location == NoteLocation::Staged
+    }
+
+    pub fn update(&mut self, readings: NoteReadings) {
+        self.update_at(readings, Instant::now());
+    }
+
+    pub fn update_at(&mut self, readings: NoteReadings, now: Instant) {
+        // keep the last debounced value for anything we couldn't read
+        if let Some(raw) = readings.intake_stalled {
+            self.stalled.update(raw, now);
+        }
+        if let Some(raw) = readings.cam_limit {
+            self.cam.update(raw, now);
+        }
+        if let Some(raw) = readings.beam_break {
+            self.beam.update(raw, now);
+        }
+
+        let stalled = self.stalled.value();
+        let cam = self.cam.value();
+        let beam = self.beam.value();
+        let in_intake = stalled || cam;
+
+        let transit_time = |since: Instant| now.duration_since(since).as_secs_f64();
+
+        let mut disagreement = None;
+        let (location, confidence) = match (in_intake, beam) {
+            (false, true) => {
+                self.transit_since = None;
+                (NoteLocation::Staged, 1.)
+            }
+            (true, false) => {
+                self.transit_since = None;
+                let confidence = match (cam, stalled) {
+                    (true, true) => 1.,
+                    (true, false) => 0.8,
+                    _ => 0.6,
+                };
+                (NoteLocation::Intake, confidence)
+            }
+            (true, true) => {
+                // fine for a moment while the note is handed off
+                let since = *self.transit_since.get_or_insert(now);
+                if transit_time(since) > TRANSIT_TIMEOUT {
+                    disagreement = Some("beam break sees a note the intake still holds");
+                    (NoteLocation::Staged, 0.5)
+                } else {
+                    (NoteLocation::Transit, 0.8)
+                }
+            }
+            (false, false) => {
+                if matches!(self.location, NoteLocation::Intake | NoteLocation::Transit) {
+                    // left the intake, should show up on the beam break soon
+                    let since = *self.transit_since.get_or_insert(now);
+                    if transit_time(since) <= TRANSIT_TIMEOUT {
+                        (NoteLocation::Transit, 0.5)
+                    } else {
+                        disagreement =
+                            Some("note left the intake and never reached the beam break");
+                        self.transit_since = None;
+                        (NoteLocation::None, 0.5)
+                    }
+                } else {
+                    self.transit_since = None;
+                    (NoteLocation::None, 1.)
+                }
+            }
+        };
+
+        if disagreement != self.disagreement {
+            if let Some(disagreement) = disagreement {
+                println!("note sensors disagree: {disagreement}");
+            }
+        }
+
+        self.location = location;
+        self.confidence = confidence;
+        self.disagreement = disagreement;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{Debouncer, NoteLocation, NoteReadings, NoteSensor};
+
+    fn readings(stalled: bool, cam: bool, beam: bool) -> NoteReadings {
+        NoteReadings {
+            intake_stalled: Some(stalled),
+            cam_limit: Some(cam),
+            beam_break: Some(beam),
+        }
+    }
+
+    /// feed the same readings for `millis`, 10ms at a time
+    fn hold(sensor: &mut NoteSensor, now: &mut Instant, readings: NoteReadings, millis: u64) {
+        for _ in 0..millis / 10 {
+            *now += Duration::from_millis(10);
+            sensor.update_at(readings, *now);
+        }
+    }
+
+    #[test]
+    fn debouncer_ignores_glitches() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(Duration::from_millis(50));
+
+        assert!(!debouncer.update(true, start));
+        assert!(!debouncer.update(false, start + Duration::from_millis(20)));
+        assert!(!debouncer.update(true, start + Duration::from_millis(40)));
+        assert!(!debouncer.update(true, start + Duration::from_millis(80)));
+        assert!(debouncer.update(true, start + Duration::from_millis(90)));
+    }
+
+    #[test]
+    fn follows_note_through_robot() {
+        let mut now = Instant::now();
+        let mut sensor = NoteSensor::default();
+
+        hold(&mut sensor, &mut now, readings(false, false, false), 200);
+        assert_eq!(sensor.location(), NoteLocation::None);
+
+        hold(&mut sensor, &mut now, readings(true, true, false), 200);
+        assert_eq!(sensor.location(), NoteLocation::Intake);
+        assert_eq!(sensor.confidence(), 1.);
+
+        hold(&mut sensor, &mut now, readings(false, false, false), 200);
+        assert_eq!(sensor.location(), NoteLocation::Transit);
+
+        hold(&mut sensor, &mut now, readings(false, false, true), 200);
+        assert_eq!(sensor.location(), NoteLocation::Staged);
+        assert!(sensor.disagreement().is_none());
+    }
+
+    #[test]
+    fn single_sample_doesnt_count() {
+        let mut now = Instant::now();
+        let mut sensor = NoteSensor::default();
+
+        hold(&mut sensor, &mut now, readings(false, false, true), 10);
+        hold(&mut sensor, &mut now, readings(false, false, false), 200);
+
+        assert_eq!(sensor.location(), NoteLocation::None);
+    }
+
+    #[test]
+    fn flags_beam_break_with_note_in_intake() {
+        let mut now = Instant::now();
+        let mut sensor = NoteSensor::default();
+
+        hold(&mut sensor, &mut now, readings(false, true, true), 3000);
+
+        assert!(sensor.disagreement().is_some());
+        assert!(sensor.confidence() < 1.);
+    }
+
+    #[test]
+    fn lost_in_transit() {
+        let mut now = Instant::now();
+        let mut sensor = NoteSensor::default();
+
+        hold(&mut sensor, &mut now, readings(false, true, false), 200);
+        hold(&mut sensor, &mut now, readings(false, false, false), 3000);
+
+        assert_eq!(sensor.location(), NoteLocation::None);
+    }
+
+    #[test]
+    fn keeps_last_value_when_unread() {
+        let mut now = Instant::now();
+        let mut sensor = NoteSensor::default();
+
+        hold(&mut sensor, &mut now, readings(false, false, true), 200);
+        hold(&mut sensor, &mut now, NoteReadings::default(), 200);
+
+        assert_eq!(sensor.location(), NoteLocation::Staged);
+    }
+}
@@ -0,0 +1,85 @@
+use std::time::{Duration, Instant};
+
+use crate::constants::shooter::*;
+
+/// Drives a note `INDEX_DISTANCE` past where the beam cleared, with the
+/// feeder in open loop
+///
+/// A P-only loop near the goal asks for less than it takes to get the
+/// feeder moving, so the output never drops below `FEEDER_KS`. A note that
+/// stops short anyway, within `INDEX_SETTLE_TOLERANCE`, counts as parked
+/// once it's held still for `INDEX_SETTLE_TIME`
+#[derive(Default)]
+pub struct Parking {
+    /// offset it last moved from, and when
+    settling: Option<(f64, Instant)>,
+}
+
+impl Parking {
+    pub fn update(&mut self, offset: f64) -> (f64, bool) {
+        self.update_at(offset, Instant::now())
+    }
+
+    /// `offset` from `Shooter::note_offset`, gives the output to feed the
+    /// note further in and whether it's arrived
+    pub fn update_at(&mut self, offset: f64, now: Instant) -> (f64, bool) {
+        let error = INDEX_DISTANCE - offset;
+        if error.abs() < INDEX_TOLERANCE {
+            return (0., true);
+        }
+
+        let output = (error * FEEDER_KP + error.signum() * FEEDER_KS)
+            .clamp(-INDEX_CREEP_SPEED, INDEX_CREEP_SPEED);
+
+        let (since_offset, since) = *self.settling.get_or_insert((offset, now));
+        if (offset - since_offset).abs() > INDEX_SETTLE_DISTANCE {
+            self.settling = Some((offset, now));
+            return (output, false);
+        }
+
+        let settled = now.duration_since(since) >= Duration::from_secs_f64(INDEX_SETTLE_TIME);
+        (output, settled && error.abs() < INDEX_SETTLE_TOLERANCE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::Parking;
+    use crate::constants::shooter::{FEEDER_KS, INDEX_DISTANCE};
+
+    /// feeder that only turns past its breakaway, and stops at `stop`
+    fn park(stop: f64) -> bool {
+        let start = Instant::now();
+        let mut parking = Parking::default();
+        let mut offset = 0.;
+
+        for tick in 0..100 {
+            let (output, arrived) =
+                parking.update_at(offset, start + Duration::from_millis(20 * tick));
+            if arrived {
+                return true;
+            }
+            if output.abs() >= FEEDER_KS {
+                offset = (offset + output * 0.5).min(stop);
+            }
+        }
+        false
+    }
+
+    #[test]
+    fn arrives_past_breakaway() {
+        assert!(park(f64::INFINITY));
+    }
+
+    #[test]
+    fn arrives_stopped_just_short() {
+        assert!(park(INDEX_DISTANCE - 0.1));
+    }
+
+    #[test]
+    fn not_parked_stopped_well_short() {
+        assert!(!park(INDEX_DISTANCE - 0.3));
+    }
+}
@@ -0,0 +1,367 @@
+use std::sync::mpsc::{self, Sender, SyncSender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use frcrs::ctre::Talon;
+use frcrs::rev::Spark;
+use j4rs::Jvm;
+use lazy_static::lazy_static;
+
+use crate::constants::power::*;
+use crate::wrapper;
+
+enum Motor {
+    Spark(Spark),
+    Talon(Talon),
+}
+
+lazy_static! {
+    /// every motor that's been configured, for the watchdog
+    static ref MOTORS: Mutex<Vec<Motor>> = Mutex::new(Vec::new());
+    /// asks the neutral thread to stop everything, with somewhere to say it's done
+    static ref NEUTRAL: Sender<SyncSender<()>> = spawn_neutral();
+    /// what `Power` last said about the battery, for sparks set from anywhere
+    static ref SPARK_SCALE: Mutex<SparkScale> = Mutex::new(SparkScale::default());
+}
+
+#[derive(Clone, Copy)]
+struct SparkScale {
+    compensation: f64,
+    shedding: bool,
+}
+
+impl Default for SparkScale {
+    fn default() -> Self {
+        Self {
+            compensation: 1.,
+            shedding: false,
+        }
+    }
+}
+
+fn spark_scale() -> SparkScale {
+    SPARK_SCALE.lock().map(|scale| *scale).unwrap_or_default()
+}
+
+/// Percent output for a spark, compensated for the battery like the talons
+/// (see `Power::spark_compensation`)
+pub fn spark_output(value: f64) -> f64 {
+    (value * spark_scale().compensation).clamp(-1., 1.)
+}
+
+/// Percent output for a mechanism that can wait out a brownout: the intake
+/// rollers, amp bar and climbers. Slowed to `SHED_MECHANISM_SCALE` while
+/// shedding rather than stopped, so a climb or an intake still finishes
+pub fn sheddable_output(value: f64) -> f64 {
+    let scale = spark_scale();
+    if scale.shedding {
+        value * SHED_MECHANISM_SCALE
+    } else {
+        (value * scale.compensation).clamp(-1., 1.)
+    }
+}
+
+/// Stopping a motor is a JNI call, which is only valid from a thread the JVM
+/// knows about. The watchdog and panic hook run on threads it doesn't, so
+/// they hand the work to this one, attached once at startup
+fn spawn_neutral() -> Sender<SyncSender<()>> {
+    let (requests, received) = mpsc::channel::<SyncSender<()>>();
+
+    let spawned = thread::Builder::new()
+        .name("neutral".to_owned())
+        .spawn(move || {
+            let _jvm = match Jvm::attach_thread() {
+                Ok(jvm) => jvm,
+                Err(e) => {
+                    println!("neutral thread couldn't attach to the jvm: {e}");
+                    return;
+                }
+            };
+
+            for done in received {
+                stop_motors();
+                let _ = done.send(());
+            }
+        });
+    if let Err(e) = spawned {
+        println!("couldn't start the neutral thread: {e}");
+    }
+
+    requests
+}
+
+fn register(motor: Motor) {
+    if let Ok(mut motors) = MOTORS.lock() {
+        motors.push(motor);
+    }
+}
+
+/// Register a spark for the watchdog
+///
+/// Nothing can reach a spark's settings once frcrs owns it, so its smart
+/// current limit is burned to flash with the REV hardware client, using the
+/// `*_CURRENT_LIMIT` constants. `check_spark` raises an alarm for one that
+/// draws past its limit. Percent output goes through `spark_output` or
+/// `sheddable_output` for voltage compensation
+pub fn configure_spark(spark: &Spark) {
+    register(Motor::Spark(spark.clone()));
+}
+
+/// talons are compensated in software, see `Power::drive_scale`
+pub fn configure_talon(talon: &Talon, id: i32, current_limit: f64) {
+    wrapper::talon_current_limit(id, current_limit);
+    register(Motor::Talon(talon.clone()));
+}
+
+/// start the neutral thread now, rather than the first time it's needed
+pub fn start_neutral() {
+    lazy_static::initialize(&NEUTRAL);
+}
+
+/// Stop every configured motor, safe to call from any thread
+///
+/// Waits up to `NEUTRAL_TIMEOUT` for the neutral thread to get it done
+pub fn neutral_all() {
+    let (done, finished) = mpsc::sync_channel(1);
+    if NEUTRAL.send(done).is_ok() {
+        let _ = finished.recv_timeout(Duration::from_secs_f64(NEUTRAL_TIMEOUT));
+    }
+}
+
+fn stop_motors() {
+    // never wait, whoever stalled or panicked may be holding it
+    let Ok(motors) = MOTORS.try_lock() else {
+        return;
+    };
+    for motor in motors.iter() {
+        match motor {
+            Motor::Spark(spark) => spark.stop(),
+            Motor::Talon(talon) => talon.stop(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PowerState {
+    Normal,
+    /// battery is sagging, drive output is scaled back
+    Sagging,
+    /// about to brown out, non-critical mechanisms are slowed too
+    Shedding,
+}
+
+impl PowerState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PowerState::Normal => "normal",
+            PowerState::Sagging => "sagging",
+            PowerState::Shedding => "shedding",
+        }
+    }
+
+    /// one step back towards normal
+    fn recovered(&self) -> PowerState {
+        match self {
+            PowerState::Normal | PowerState::Sagging => PowerState::Normal,
+            PowerState::Shedding => PowerState::Sagging,
+        }
+    }
+
+    fn threshold(&self) -> f64 {
+        match self {
+            PowerState::Normal => f64::INFINITY,
+            PowerState::Sagging => SAG_VOLTAGE,
+            PowerState::Shedding => SHED_VOLTAGE,
+        }
+    }
+}
+
+/// Battery voltage and the load shedding policy that goes with it
+///
+/// Steps down as soon as the voltage dips, but only steps back up once it
+/// has stayed `RECOVERY_HYSTERESIS` above the threshold for `RECOVERY_TIME`
+pub struct Power {
+    voltage: f64,
+    state: PowerState,
+    recovering_since: Option<Instant>,
+}
+
+impl Default for Power {
+    fn default() -> Self {
+        Self {
+            voltage: NOMINAL_VOLTAGE,
+            state: PowerState::Normal,
+            recovering_since: None,
+        }
+    }
+}
+
+impl Power {
+    pub fn voltage(&self) -> f64 {
+        self.voltage
+    }
+
+    pub fn state(&self) -> PowerState {
+        self.state
+    }
+
+    /// pause anything that isn't needed to drive, score or climb
+    pub fn shedding(&self) -> bool {
+        self.state == PowerState::Shedding
+    }
+
+    /// multiplier for percent output, so the same command gives the same
+    /// speed on a tired battery
+    pub fn compensation(&self) -> f64 {
+        NOMINAL_VOLTAGE / self.voltage.max(1.)
+    }
+
+    /// multiplier for spark percent output, compensated only while the
+    /// battery is healthy for the same reason as `drive_scale`
+    pub fn spark_compensation(&self) -> f64 {
+        match self.state {
+            PowerState::Normal => self.compensation(),
+            PowerState::Sagging | PowerState::Shedding => 1.,
+        }
+    }
+
+    /// multiplier for drive percent output: compensated while the battery
+    /// is healthy, shed once it isn't. Compensating a sagging battery would
+    /// ask it for more current just as it's running out
+    pub fn drive_scale(&self) -> f64 {
+        match self.state {
+            PowerState::Normal => self.compensation(),
+            PowerState::Sagging => SAG_DRIVE_SCALE,
+            PowerState::Shedding => SHED_DRIVE_SCALE,
+        }
+    }
+
+    /// `browned_out` comes from the HAL, and always means shedding
+    pub fn update(&mut self, voltage: f64, browned_out: bool) {
+        self.update_at(voltage, browned_out, Instant::now());
+
+        if let Ok(mut scale) = SPARK_SCALE.lock() {
+            *scale = SparkScale {
+                compensation: self.spark_compensation(),
+                shedding: self.shedding(),
+            };
+        }
+    }
+
+    pub fn update_at(&mut self, voltage: f64, browned_out: bool, now: Instant) {
+        self.voltage = voltage;
+
+        let measured = if browned_out || voltage < SHED_VOLTAGE {
+            PowerState::Shedding
+        } else if voltage < SAG_VOLTAGE {
+            PowerState::Sagging
+        } else {
+            PowerState::Normal
+        };
+
+        if measured >= self.state {
+            self.recovering_since = None;
+            self.set_state(measured);
+            return;
+        }
+
+        if voltage < self.state.threshold() + RECOVERY_HYSTERESIS {
+            self.recovering_since = None;
+            return;
+        }
+
+        // one state at a time, so shedding recovers through sagging and its
+        // hysteresis rather than straight to normal
+        let since = *self.recovering_since.get_or_insert(now);
+        if now.duration_since(since) >= Duration::from_secs_f64(RECOVERY_TIME) {
+            self.recovering_since = None;
+            self.set_state(self.state.recovered());
+        }
+    }
+
+    fn set_state(&mut self, state: PowerState) {
+        if state != self.state {
+            println!(
+                "power {} at {:.2}v (was {})",
+                state.name(),
+                self.voltage,
+                self.state.name()
+            );
+        }
+        self.state = state;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{Power, PowerState};
+
+    #[test]
+    fn steps_down_immediately() {
+        let now = Instant::now();
+        let mut power = Power::default();
+
+        power.update_at(12.5, false, now);
+        assert_eq!(power.state(), PowerState::Normal);
+
+        power.update_at(9., false, now);
+        assert_eq!(power.state(), PowerState::Sagging);
+        assert!(power.drive_scale() < 1.);
+
+        power.update_at(7.5, false, now);
+        assert!(power.shedding());
+        assert!(power.drive_scale() < 1.);
+
+        power.update_at(11., true, now);
+        assert!(power.shedding());
+        assert!(power.drive_scale() < 1.);
+    }
+
+    #[test]
+    fn recovers_with_hysteresis() {
+        let start = Instant::now();
+        let mut power = Power::default();
+
+        power.update_at(7.5, false, start);
+        assert!(power.shedding());
+
+        // just over the line isn't enough
+        power.update_at(8.2, false, start + Duration::from_millis(100));
+        power.update_at(8.2, false, start + Duration::from_secs(2));
+        assert!(power.shedding());
+
+        power.update_at(8.6, false, start + Duration::from_secs(3));
+        power.update_at(8.6, false, start + Duration::from_millis(3400));
+        assert!(power.shedding());
+        power.update_at(8.6, false, start + Duration::from_millis(3500));
+        assert_eq!(power.state(), PowerState::Sagging);
+    }
+
+    #[test]
+    fn recovers_one_state_at_a_time() {
+        let start = Instant::now();
+        let mut power = Power::default();
+
+        power.update_at(7.5, false, start);
+        assert!(power.shedding());
+        assert_eq!(power.spark_compensation(), 1.);
+
+        // a full battery still has to hold through sagging first
+        power.update_at(12., false, start + Duration::from_millis(100));
+        power.update_at(12., false, start + Duration::from_millis(600));
+        assert_eq!(power.state(), PowerState::Sagging);
+
+        power.update_at(12., false, start + Duration::from_millis(700));
+        assert_eq!(power.state(), PowerState::Sagging);
+        power.update_at(12., false, start + Duration::from_millis(1100));
+        assert_eq!(power.state(), PowerState::Sagging);
+        power.update_at(12., false, start + Duration::from_millis(1200));
+        assert_eq!(power.state(), PowerState::Normal);
+
+        power.update_at(10., false, start + Duration::from_millis(1300));
+        assert!(power.spark_compensation() > 1.);
+    }
+}
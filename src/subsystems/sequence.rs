@@ -0,0 +1,199 @@
+use std::{
+    fmt,
+    future::Future,
+    ops::{Deref, DerefMut},
+    time::{Duration, Instant},
+};
+
+use tokio::time::sleep;
+
+/// How a wait or sequence ended, `Ok(())` means it finished
+pub type Outcome = Result<(), Interrupted>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interrupted {
+    TimedOut,
+    /// dropped before finishing, usually an aborted task
+    Cancelled,
+    /// a mechanism got stuck and retrying didn't free it
+    Jammed,
+}
+
+impl Interrupted {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Interrupted::TimedOut => "timed out",
+            Interrupted::Cancelled => "cancelled",
+            Interrupted::Jammed => "jammed",
+        }
+    }
+}
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// for telemetry
+pub fn describe(outcome: Outcome) -> &'static str {
+    match outcome {
+        Ok(()) => "done",
+        Err(interrupted) => interrupted.name(),
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+/// Poll `condition` every 20ms until it's true, forever
+pub async fn wait<F>(mut condition: F)
+where
+    F: FnMut() -> bool,
+{
+    loop {
+        if condition() {
+            return;
+        };
+        sleep(Duration::from_millis(20)).await;
+    }
+}
+
+/// Poll `condition` every 20ms until it's true or `timeout` runs out
+pub async fn wait_for<F>(mut condition: F, timeout: Duration) -> Outcome
+where
+    F: FnMut() -> bool,
+{
+    let started = Instant::now();
+    loop {
+        if condition() {
+            return Ok(());
+        }
+        if started.elapsed() > timeout {
+            return Err(Interrupted::TimedOut);
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+}
+
+/// Run a whole sequence with a deadline, dropping it (and running its cleanup) if it's late
+pub async fn within<F>(timeout: Duration, sequence: F) -> Outcome
+where
+    F: Future<Output = Outcome>,
+{
+    match tokio::time::timeout(timeout, sequence).await {
+        Ok(outcome) => outcome,
+        Err(_) => Err(Interrupted::TimedOut),
+    }
+}
+
+/// Subsystems borrowed by a sequence, with a cleanup that stops them however it ends
+///
+/// The cleanup runs on drop, so an aborted task or a timeout wrapped
+/// around the sequence still leaves the motors stopped
+pub struct Sequence<T, F>
+where
+    F: FnMut(&mut T),
+{
+    name: &'static str,
+    subsystems: T,
+    cleanup: F,
+    outcome: Option<Outcome>,
+}
+
+impl<T, F> Sequence<T, F>
+where
+    F: FnMut(&mut T),
+{
+    pub fn new(name: &'static str, subsystems: T, cleanup: F) -> Self {
+        Self {
+            name,
+            subsystems,
+            cleanup,
+            outcome: None,
+        }
+    }
+
+    /// record how the sequence went, and clean up
+    pub fn finish(mut self, outcome: Outcome) -> Outcome {
+        self.outcome = Some(outcome);
+        outcome
+    }
+}
+
+impl<T, F> Deref for Sequence<T, F>
+where
+    F: FnMut(&mut T),
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.subsystems
+    }
+}
+
+impl<T, F> DerefMut for Sequence<T, F>
+where
+    F: FnMut(&mut T),
+{
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.subsystems
+    }
+}
+
+impl<T, F> Drop for Sequence<T, F>
+where
+    F: FnMut(&mut T),
+{
+    fn drop(&mut self) {
+        if let Err(interrupted) = self.outcome.unwrap_or(Err(Interrupted::Cancelled)) {
+            println!("{} {}", self.name, interrupted);
+        }
+        (self.cleanup)(&mut self.subsystems);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, time::Duration};
+
+    use tokio::time::sleep;
+
+    use super::{wait_for, within, Interrupted, Sequence};
+
+    #[tokio::test]
+    async fn wait_for_times_out() {
+        let outcome = wait_for(|| false, Duration::from_millis(50)).await;
+        assert_eq!(outcome, Err(Interrupted::TimedOut));
+
+        let outcome = wait_for(|| true, Duration::from_millis(50)).await;
+        assert_eq!(outcome, Ok(()));
+    }
+
+    #[test]
+    fn cleanup_runs_when_finished() {
+        let stopped = Cell::new(false);
+
+        let sequence = Sequence::new("test", &stopped, |stopped| stopped.set(true));
+        assert_eq!(sequence.finish(Ok(())), Ok(()));
+
+        assert!(stopped.get());
+    }
+
+    #[tokio::test]
+    async fn cleanup_runs_on_timeout() {
+        let stopped = Cell::new(false);
+        let running = Cell::new(false);
+
+        let outcome = within(Duration::from_millis(50), async {
+            let sequence = Sequence::new("test", &stopped, |stopped| stopped.set(true));
+            running.set(true);
+            sleep(Duration::from_secs(10)).await;
+            sequence.finish(Ok(()))
+        })
+        .await;
+
+        assert_eq!(outcome, Err(Interrupted::TimedOut));
+        assert!(running.get());
+        assert!(stopped.get());
+    }
+}
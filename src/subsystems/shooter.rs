@@ -1,11 +1,19 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use crate::constants::power::{FEEDER_CURRENT_LIMIT, FLYWHEEL_CURRENT_LIMIT};
+use crate::constants::shooter::*;
 use crate::constants::*;
 use frcrs::dio::DIO;
 use frcrs::rev::MotorType::Brushless;
 use frcrs::rev::Spark;
+use tokio::time::sleep;
 use uom::si::angle::revolution;
-use uom::si::f64::Angle;
 
-use super::wait;
+use super::{
+    check_spark, configure_spark, spark_output, wait_for, Alarm, AmpBar, AmpBarState, Interrupted,
+    Outcome, Parking, Sequence,
+};
 
 pub struct Shooter {
     feeder_top: Spark,
@@ -14,26 +22,62 @@ pub struct Shooter {
     shooter_top: Spark,
     shooter_bottom: Spark,
 
-    amp_bar: Spark,
+    amp_bar: AmpBar,
 
     staged: DIO,
+    /// feeder positions (top, bottom) where the beam last cleared
+    index_edge: Cell<Option<(f64, f64)>>,
 }
 
 impl Shooter {
     pub fn new() -> Self {
+        let feeder_top = Spark::new(SHOOTER_FEEDER_TOP, Brushless);
+        let feeder_bottom = Spark::new(SHOOTER_FEEDER_BOTTOM, Brushless);
+        configure_spark(&feeder_top);
+        configure_spark(&feeder_bottom);
+
+        let shooter_top = Spark::flex(SHOOTER_TOP);
+        let shooter_bottom = Spark::flex(SHOOTER_BOTTOM);
+        configure_spark(&shooter_top);
+        configure_spark(&shooter_bottom);
+
         Self {
-            feeder_top: Spark::new(SHOOTER_FEEDER_TOP, Brushless),
-            feeder_bottom: Spark::new(SHOOTER_FEEDER_BOTTOM, Brushless),
+            feeder_top,
+            feeder_bottom,
 
-            shooter_top: Spark::flex(SHOOTER_TOP),
-            shooter_bottom: Spark::flex(SHOOTER_BOTTOM),
+            shooter_top,
+            shooter_bottom,
 
-            amp_bar: Spark::new(AMP_BAR, Brushless),
+            amp_bar: AmpBar::new(),
 
             staged: DIO::new(BEAM_BREAK_SIGNAL),
+            index_edge: Cell::new(None),
         }
     }
 
+    pub fn health(&self, alarms: &mut Vec<Alarm>) {
+        check_spark(alarms, "feeder top", &self.feeder_top, FEEDER_CURRENT_LIMIT);
+        check_spark(
+            alarms,
+            "feeder bottom",
+            &self.feeder_bottom,
+            FEEDER_CURRENT_LIMIT,
+        );
+        check_spark(
+            alarms,
+            "flywheel top",
+            &self.shooter_top,
+            FLYWHEEL_CURRENT_LIMIT,
+        );
+        check_spark(
+            alarms,
+            "flywheel bottom",
+            &self.shooter_bottom,
+            FLYWHEEL_CURRENT_LIMIT,
+        );
+        self.amp_bar.health(alarms);
+    }
+
     pub fn stop_feeder(&self) {
         self.feeder_top.stop();
         self.feeder_bottom.stop();
@@ -44,58 +88,167 @@ impl Shooter {
         self.shooter_bottom.stop();
     }
 
-    pub fn stop(&self) {
+    pub fn stop(&mut self) {
         self.feeder_top.stop();
         self.feeder_bottom.stop();
 
         self.shooter_top.stop();
         self.shooter_bottom.stop();
+
+        self.amp_bar.stop();
     }
 
     pub fn set_feeder(&self, value: f64) {
+        let value = spark_output(value);
         self.feeder_top.set(value);
         self.feeder_bottom.set(-value);
     }
 
+    pub fn feeder_current(&self) -> f64 {
+        self.feeder_top
+            .get_current()
+            .max(self.feeder_bottom.get_current())
+    }
+
+    /// feeder (top, bottom) revolutions
+    fn feeder_positions(&self) -> (f64, f64) {
+        (
+            self.feeder_top.get_position().get::<revolution>(),
+            self.feeder_bottom.get_position().get::<revolution>(),
+        )
+    }
+
+    /// feeder revolutions towards the flywheels since the beam last cleared
+    pub fn note_offset(&self) -> Option<f64> {
+        self.index_edge
+            .get()
+            .map(|(top, _)| top - self.feeder_positions().0)
+    }
+
+    /// the note is parked where `index_note` puts it
+    pub fn indexed(&self) -> bool {
+        self.note_offset()
+            .is_some_and(|offset| (offset - INDEX_DISTANCE).abs() < INDEX_TOLERANCE)
+    }
+
+    /// Creep a staged note until the beam clears, then park it `INDEX_DISTANCE` past that edge
+    ///
+    /// Uses the feeder encoders so the note ends up at the same depth
+    /// regardless of battery or friction
+    pub async fn index_note(&self) -> Outcome {
+        let sequence = Sequence::new("index", self, |shooter| shooter.stop_feeder());
+
+        let outcome = async {
+            let started = Instant::now();
+            self.index_edge.set(None);
+            self.set_feeder(-INDEX_CREEP_SPEED);
+
+            // poll fast, the edge is only as good as when we see it
+            while self.contains_note() {
+                if started.elapsed().as_secs_f64() > INDEX_TIMEOUT {
+                    return Err(Interrupted::TimedOut);
+                }
+                sleep(Duration::from_millis(5)).await;
+            }
+
+            self.index_edge.set(Some(self.feeder_positions()));
+
+            let mut parking = Parking::default();
+            let parked = || {
+                let offset = self.note_offset().unwrap_or(0.);
+                let (output, arrived) = parking.update(offset);
+                // feeding is negative
+                self.set_feeder(-output);
+                arrived
+            };
+            wait_for(parked, Duration::from_secs_f64(INDEX_TIMEOUT)).await
+        }
+        .await;
+
+        sequence.finish(outcome)
+    }
+
+    /// rpm of the slower feeder wheel
+    pub fn feeder_velocity(&self) -> f64 {
+        self.feeder_top
+            .get_velocity()
+            .abs()
+            .min(self.feeder_bottom.get_velocity().abs())
+    }
+
     pub fn stow_amp(&mut self) {
-        self.amp_bar
-            .set_position(Angle::new::<revolution>(amp::STOWED_POSITION));
+        self.amp_bar.stow();
     }
 
     pub fn deploy_amp(&mut self) {
-        self.amp_bar
-            .set_position(Angle::new::<revolution>(amp::DEPLOYED_POSITION));
+        self.amp_bar.deploy();
     }
 
     pub fn amp_deployed(&mut self) -> bool {
-        self.amp_bar.get_position().get::<revolution>()
-            < (amp::DEPLOYED_POSITION + amp::STOWED_POSITION) / 2.
+        self.amp_bar.deployed()
+    }
+
+    /// amp bar finished moving to where it was last sent
+    pub fn amp_at_goal(&self) -> bool {
+        self.amp_bar.at_goal()
+    }
+
+    pub fn amp_moving(&self) -> bool {
+        self.amp_bar.moving()
     }
 
-    pub fn set_amp_bar(&self, value: f64) {
+    pub fn amp_state(&self) -> AmpBarState {
+        self.amp_bar.state()
+    }
+
+    pub fn amp_position(&self) -> f64 {
+        self.amp_bar.position()
+    }
+
+    /// home and profile the amp bar, call every loop
+    pub fn update_amp(&mut self, dt: &Duration) {
+        self.amp_bar.update(dt);
+    }
+
+    /// open loop, respects soft limits once homed
+    pub fn set_amp_bar(&mut self, value: f64) {
         self.amp_bar.set(value);
     }
 
     pub fn set_shooter(&self, value: f64) {
+        let value = spark_output(value);
         self.shooter_top.set(value);
         self.shooter_bottom.set(-value);
     }
 
     pub fn set_velocity(&mut self, value: f64) {
+        self.set_velocities(value, value);
+    }
+
+    /// seperate top and bottom rpm, for putting spin on the note
+    pub fn set_velocities(&mut self, top: f64, bottom: f64) {
         self.shooter_top
-            .set_reference(value, frcrs::rev::ControlType::Velocity);
+            .set_reference(top, frcrs::rev::ControlType::Velocity);
         self.shooter_bottom
-            .set_reference(-value, frcrs::rev::ControlType::Velocity);
+            .set_reference(-bottom, frcrs::rev::ControlType::Velocity);
     }
 
     pub fn contains_note(&self) -> bool {
         !self.staged.get()
     }
 
-    pub async fn load(&self) {
-        self.set_feeder(-0.2);
-        wait(|| self.contains_note()).await;
-        self.stop_feeder();
+    /// pull a note onto the beam break with the feeder
+    pub async fn load(&self) -> Outcome {
+        let sequence = Sequence::new("load", self, |shooter| shooter.stop_feeder());
+
+        sequence.set_feeder(-0.2);
+        let outcome = wait_for(
+            || sequence.contains_note(),
+            Duration::from_secs_f64(shooter::LOAD_TIMEOUT),
+        )
+        .await;
+
+        sequence.finish(outcome)
     }
 
     pub fn get_velocity(&mut self) -> f64 {
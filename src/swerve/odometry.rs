@@ -1,6 +1,9 @@
-use std::{ops::Sub, time::Instant};
+use std::{
+    collections::VecDeque,
+    ops::Sub,
+    time::{Duration, Instant},
+};
 
-use frcrs::alliance_station;
 use nalgebra::{Rotation2, Vector2};
 use uom::si::{
     angle::radian,
@@ -8,7 +11,11 @@ use uom::si::{
     length::meter,
 };
 
-use crate::{constants::HALF_FIELD_WIDTH_METERS, telemetry::TelemetryStore};
+use crate::{
+    constants::vision::{ODOMETRY_HISTORY, ODOMETRY_STD_DEV},
+    field::AllianceFlip,
+    telemetry::TelemetryStore,
+};
 
 #[derive(Default, Clone)]
 pub struct ModuleReturn {
@@ -39,44 +46,98 @@ impl Sub for ModuleReturn {
 pub struct Odometry {
     last_modules: Vec<ModuleReturn>,
     pub position: Vector2<f64>,
+    /// field relative, meters per second
+    pub velocity: Vector2<f64>,
+    last_update: Instant,
     last_apriltag: Instant,
+    /// where we were at each update, oldest first, for putting vision
+    /// measurements back in the present
+    history: VecDeque<(Instant, Vector2<f64>)>,
 }
 
 impl Odometry {
     pub fn new() -> Self {
         let last_modules = Vec::new();
         let position = Vector2::new(0., 0.);
+        let velocity = Vector2::new(0., 0.);
+        let last_update = Instant::now();
         let last_apriltag = Instant::now();
         Self {
             last_modules,
             position,
+            velocity,
+            last_update,
             last_apriltag,
+            history: VecDeque::new(),
         }
     }
 
+    /// `position` as drawn for blue
     pub fn set(&mut self, position: Vector2<f64>) {
-        if alliance_station().red() {
-            self.position.x = position.x;
-            self.position.y = HALF_FIELD_WIDTH_METERS - position.y;
-        } else {
-            self.position = position;
-        }
+        self.reset(AllianceFlip::current().position(position));
+    }
+
+    /// `position` already in our odometry, like a vision measurement
+    pub fn reset(&mut self, position: Vector2<f64>) {
+        self.position = position;
+        // what came before the jump says nothing about where we are now
+        self.history.clear();
     }
 
-    pub async fn update_from_vision(&mut self, telemetry: TelemetryStore, _mirror: bool) {
-        if let Some((time, pose)) = &telemetry.read().await.apriltag_pose {
-            if self.last_apriltag >= *time {
-                return;
+    /// Jump to the most precise measurement captured since the last one
+    /// used, moved on by however far we've driven since it was captured
+    ///
+    /// Only measurements within `ODOMETRY_STD_DEV` and young enough to still
+    /// be in the history count. Every loop while driving, from teleop's
+    /// `container` and `follow_path`
+    pub async fn update_from_vision(&mut self, telemetry: TelemetryStore) {
+        let telemetry = telemetry.read().await;
+        let measurements = telemetry.vision.since(self.last_apriltag);
+        let kept = Duration::from_secs_f64(ODOMETRY_HISTORY);
+
+        let best = measurements
+            .iter()
+            .filter(|measurement| {
+                measurement.position_error() <= ODOMETRY_STD_DEV
+                    && measurement.captured.elapsed() <= kept
+            })
+            .min_by(|a, b| a.position_error().total_cmp(&b.position_error()));
+        if let Some(best) = best {
+            if let Some(then) = self.position_at(best.captured) {
+                self.correct(best.position - then);
             }
+        }
+        if let Some(newest) = measurements.last() {
+            self.last_apriltag = newest.captured;
+        }
+    }
+
+    /// where we were at `time`, none if it's from before the oldest update
+    /// kept
+    fn position_at(&self, time: Instant) -> Option<Vector2<f64>> {
+        let after = self
+            .history
+            .partition_point(|(updated, _)| *updated <= time);
+        let (_, position) = self.history.get(after.checked_sub(1)?)?;
+        Some(*position)
+    }
 
-            self.set(Vector2::new(pose.x, pose.y));
-            self.last_apriltag = time.clone();
+    /// move by `correction`, and everywhere we've been with it, so later
+    /// measurements are compared against the corrected path
+    fn correct(&mut self, correction: Vector2<f64>) {
+        self.position += correction;
+        for (_, position) in &mut self.history {
+            *position += correction;
         }
     }
 
     pub fn calculate(&mut self, positions: Vec<ModuleReturn>, angle: Angle) {
+        let dt = self.last_update.elapsed().as_secs_f64();
+        self.last_update = Instant::now();
+
         if positions.len() != self.last_modules.len() {
             self.last_modules = positions;
+            self.velocity = Vector2::zeros();
             return;
         }
 
@@ -97,7 +158,20 @@ impl Odometry {
 
         delta /= positions.len() as f64;
 
+        if dt > 0. {
+            self.velocity = delta / dt;
+        }
         self.position += delta;
         self.last_modules = positions;
+
+        self.history.push_back((self.last_update, self.position));
+        let kept = Duration::from_secs_f64(ODOMETRY_HISTORY);
+        while self
+            .history
+            .front()
+            .is_some_and(|(updated, _)| self.last_update - *updated > kept)
+        {
+            self.history.pop_front();
+        }
     }
 }
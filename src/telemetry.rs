@@ -14,15 +14,23 @@ use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 
 use crate::auto::Auto;
+use crate::crash::{self, CrashReport};
+use crate::field::AllianceFlip;
+use crate::vision::{Batch, Vision};
 
 pub type TelemetryStore = Arc<RwLock<Telemetry>>;
 
+/// picks `Telemetry::auto` by its id
+pub const AUTO_CHOOSER: &str = "auto chooser";
+
 lazy_static! {
     pub static ref TELEMETRY: TelemetryStore = {
         let mut telemetry: Telemetry = Default::default();
-        telemetry
-            .data
-            .insert("auto chooser".to_owned(), Data::Picker(Auto::picker()));
+        telemetry.auto = Auto::load();
+        telemetry.data.insert(
+            AUTO_CHOOSER.to_owned(),
+            Data::Picker(telemetry.auto.picker()),
+        );
         Arc::new(RwLock::new(telemetry))
     };
 }
@@ -38,9 +46,10 @@ pub enum Data {
 
 #[derive(Default)]
 pub struct Telemetry {
+    /// the auto picked by `AUTO_CHOOSER`
     pub auto: Auto,
     pub data: HashMap<String, Data>,
-    pub apriltag_pose: Option<(Instant, Pose)>,
+    pub vision: Vision,
 }
 
 #[derive(Serialize, Deserialize, Default, Clone)]
@@ -53,7 +62,10 @@ pub struct Pose {
 #[derive(Serialize, Deserialize, Default, Clone)]
 pub struct Picker {
     pub options: Vec<String>,
-    pub selected: String, // this is a number
+    /// what to show for each of `options`
+    #[serde(default)]
+    pub labels: Vec<String>,
+    pub selected: String, // one of options
 }
 
 pub fn server() -> Router<TelemetryStore> {
@@ -64,10 +76,11 @@ pub fn server() -> Router<TelemetryStore> {
         .route("/:path/:path", get(frontend))
         .route("/:path", get(frontend)) // I want to kill myself :)
         .route("/", get(frontend)) // I want to kill myself :)
-        .route("/set_position", post(set_position))
+        .route("/vision", post(vision))
         .route("/get/:key", get(get_key))
         .route("/set/:key", post(set_key))
-        .route("/get_keys", get(get_keys));
+        .route("/get_keys", get(get_keys))
+        .route("/crashes", get(crashes));
 
     router
 }
@@ -76,9 +89,39 @@ async fn set_key(
     Path(key): Path<String>,
     State(state): State<TelemetryStore>,
     Json(data): Json<Data>,
-) -> &'static str {
+) -> Result<&'static str, (StatusCode, String)> {
+    if key == AUTO_CHOOSER {
+        return set_auto(&state, data).await;
+    }
+
     state.write().await.data.insert(key, data);
-    "Success"
+    Ok("Success")
+}
+
+/// only take autos we have, and keep them across reboots
+async fn set_auto(
+    state: &TelemetryStore,
+    data: Data,
+) -> Result<&'static str, (StatusCode, String)> {
+    let Data::Picker(picker) = data else {
+        return Err((StatusCode::BAD_REQUEST, "expected a picker".to_owned()));
+    };
+    let Some(auto) = Auto::from_id(&picker.selected) else {
+        let message = format!("no auto with id {:?}", picker.selected);
+        return Err((StatusCode::BAD_REQUEST, message));
+    };
+
+    println!("auto set to {} ({})", auto.id(), auto.name());
+    auto.store();
+
+    let mut state = state.write().await;
+    // rebuilt rather than trusting the options we were sent
+    state
+        .data
+        .insert(AUTO_CHOOSER.to_owned(), Data::Picker(auto.picker()));
+    state.auto = auto;
+
+    Ok("Success")
 }
 
 async fn get_key(
@@ -92,12 +135,24 @@ async fn get_keys(State(state): State<TelemetryStore>) -> Json<Vec<String>> {
     Json(state.read().await.data.keys().cloned().collect())
 }
 
-async fn set_position(State(state): State<TelemetryStore>, Json(pose): Json<Pose>) -> &'static str {
-    let pose = (Instant::now(), pose);
-    println!("apriltag pose at x{} y{}", pose.1.x, pose.1.y);
-    state.write().await.apriltag_pose = Some(pose);
+async fn crashes() -> Json<Vec<CrashReport>> {
+    Json(crash::recent(std::path::Path::new(crash::DIRECTORY)))
+}
 
-    "written"
+async fn vision(
+    State(state): State<TelemetryStore>,
+    Json(batch): Json<Batch>,
+) -> Result<String, (StatusCode, String)> {
+    let received = Instant::now();
+    let flip = AllianceFlip::current();
+
+    match state.write().await.vision.ingest(batch, received, &flip) {
+        Ok(count) => Ok(format!("kept {count}")),
+        Err(err) => {
+            println!("refused vision batch, {err}");
+            Err((StatusCode::BAD_REQUEST, err))
+        }
+    }
 }
 
 static STATIC_DIR: Dir<'_> = include_dir::include_dir!("$CARGO_MANIFEST_DIR/talon-board/out");
@@ -142,3 +197,11 @@ pub async fn put_bool(key: &str, value: bool) {
         .data
         .insert(key.to_owned(), Data::Bool(value));
 }
+
+pub async fn put_text(key: &str, value: &str) {
+    TELEMETRY
+        .write()
+        .await
+        .data
+        .insert(key.to_owned(), Data::Text(value.to_owned()));
+}
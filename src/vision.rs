@@ -0,0 +1,357 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+use uom::si::{angle::degree, f64::Angle};
+
+use crate::{
+    constants::vision::*,
+    field::{AllianceFlip, FieldPose, LAYOUT},
+};
+
+/// What the cameras send: everything they've seen since last time
+///
+/// Timestamps are seconds on the sender's clock, whatever it is. Only the
+/// difference from `sent` matters, which is how long ago each frame was
+/// captured when the batch left
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Batch {
+    pub sent: f64,
+    pub observations: Vec<Observation>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Observation {
+    pub camera: String,
+    pub captured: f64,
+    pub tags: Vec<Sighting>,
+    pub pose: Estimate,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct Sighting {
+    pub id: u32,
+    /// meters from the camera
+    pub distance: f64,
+    /// 0 is certain, 1 is a coin flip
+    pub ambiguity: f64,
+}
+
+/// Where the camera thinks the robot is, in field coordinates, the same on
+/// either alliance. Put in our odometry as it's ingested, so everything past
+/// `Vision::ingest` can use it as is
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct Estimate {
+    /// meters
+    pub x: f64,
+    pub y: f64,
+    pub theta: f64, // degrees
+    /// standard deviations of x, y (meters) and theta (degrees)
+    pub std_devs: [f64; 3],
+}
+
+/// One observation once it's been checked and put on our clock and in our
+/// odometry
+#[derive(Clone, Debug, PartialEq)]
+pub struct Measurement {
+    pub captured: Instant,
+    pub tags: Vec<Sighting>,
+    pub position: Vector2<f64>,
+    pub heading: Angle,
+    /// meters
+    pub position_std_dev: Vector2<f64>,
+    pub heading_std_dev: Angle,
+}
+
+impl Measurement {
+    /// the larger of the x and y standard deviations, in meters
+    pub fn position_error(&self) -> f64 {
+        self.position_std_dev.x.max(self.position_std_dev.y)
+    }
+}
+
+/// The last `BUFFER_LENGTH` measurements from each camera, oldest first
+#[derive(Default, Clone, Debug)]
+pub struct Vision {
+    cameras: HashMap<String, VecDeque<Measurement>>,
+}
+
+impl Vision {
+    /// check every observation in `batch`, then keep them all or none
+    pub fn ingest(
+        &mut self,
+        batch: Batch,
+        received: Instant,
+        flip: &AllianceFlip,
+    ) -> Result<usize, String> {
+        let measurements = batch
+            .observations
+            .iter()
+            .enumerate()
+            .map(|(i, observation)| {
+                observation
+                    .measurement(batch.sent, received, flip)
+                    .map(|measurement| (observation.camera.clone(), measurement))
+                    .map_err(|err| format!("observation {i}: {err}"))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let count = measurements.len();
+        for (camera, measurement) in measurements {
+            self.push(camera, measurement);
+        }
+
+        Ok(count)
+    }
+
+    pub fn push(&mut self, camera: String, measurement: Measurement) {
+        let buffer = self.cameras.entry(camera).or_default();
+
+        // batches can arrive out of order, keep each buffer sorted
+        let index = buffer.partition_point(|old| old.captured <= measurement.captured);
+        buffer.insert(index, measurement);
+
+        while buffer.len() > BUFFER_LENGTH {
+            buffer.pop_front();
+        }
+    }
+
+    pub fn cameras(&self) -> impl Iterator<Item = &str> {
+        self.cameras.keys().map(String::as_str)
+    }
+
+    pub fn camera(&self, camera: &str) -> Option<&VecDeque<Measurement>> {
+        self.cameras.get(camera)
+    }
+
+    /// captured after `time` by any camera, oldest first
+    pub fn since(&self, time: Instant) -> Vec<&Measurement> {
+        let mut measurements: Vec<&Measurement> = self
+            .cameras
+            .values()
+            .flat_map(|buffer| buffer.iter().filter(|m| m.captured > time))
+            .collect();
+
+        measurements.sort_by_key(|measurement| measurement.captured);
+        measurements
+    }
+
+    /// the most recent from any camera
+    pub fn latest(&self) -> Option<&Measurement> {
+        self.cameras
+            .values()
+            .filter_map(|buffer| buffer.back())
+            .max_by_key(|measurement| measurement.captured)
+    }
+}
+
+impl Observation {
+    fn measurement(
+        &self,
+        sent: f64,
+        received: Instant,
+        flip: &AllianceFlip,
+    ) -> Result<Measurement, String> {
+        if self.camera.is_empty() {
+            return Err("no camera name".to_owned());
+        }
+        if self.tags.is_empty() {
+            return Err("no tags".to_owned());
+        }
+
+        let age = sent - self.captured;
+        if age < 0. {
+            return Err(format!("captured {:.3}s after it was sent", -age));
+        }
+        if age.is_nan() || age > MAX_AGE {
+            return Err(format!("captured {age:.3}s before it was sent"));
+        }
+
+        for tag in &self.tags {
+            tag.validate()?;
+        }
+        self.pose.validate()?;
+
+        let heading = Angle::new::<degree>(self.pose.theta);
+        let pose = flip.from_field(FieldPose::new(self.pose.x, self.pose.y, heading));
+        let [x, y, theta] = self.pose.std_devs;
+        Ok(Measurement {
+            captured: received
+                .checked_sub(Duration::from_secs_f64(age))
+                .unwrap_or(received),
+            tags: self.tags.clone(),
+            position: pose.position,
+            heading: pose.heading,
+            position_std_dev: Vector2::new(x, y),
+            heading_std_dev: Angle::new::<degree>(theta),
+        })
+    }
+}
+
+impl Sighting {
+    fn validate(&self) -> Result<(), String> {
+        if LAYOUT.tag(self.id).is_none() {
+            return Err(format!("no tag {} on this field", self.id));
+        }
+        if !self.distance.is_finite() || self.distance <= 0. {
+            return Err(format!("tag {} is {}m away", self.id, self.distance));
+        }
+        if !(0. ..=1.).contains(&self.ambiguity) {
+            let ambiguity = self.ambiguity;
+            return Err(format!("tag {} ambiguity {ambiguity} isn't 0-1", self.id));
+        }
+
+        Ok(())
+    }
+}
+
+impl Estimate {
+    fn validate(&self) -> Result<(), String> {
+        let on_field = |value: f64, size: f64| {
+            value.is_finite() && (-FIELD_MARGIN..=size + FIELD_MARGIN).contains(&value)
+        };
+        if !on_field(self.x, LAYOUT.length) || !on_field(self.y, LAYOUT.width) {
+            return Err(format!("pose ({}, {}) is off the field", self.x, self.y));
+        }
+        if !self.theta.is_finite() {
+            return Err("pose has no heading".to_owned());
+        }
+        if !self
+            .std_devs
+            .iter()
+            .all(|std_dev| std_dev.is_finite() && *std_dev > 0.)
+        {
+            return Err(format!("bad std devs {:?}", self.std_devs));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use uom::si::angle::degree;
+
+    use super::{Batch, Estimate, Observation, Sighting, Vision};
+    use crate::{
+        constants::{vision::BUFFER_LENGTH, RED_REFLECTION_METERS},
+        field::{AllianceFlip, LAYOUT},
+    };
+
+    fn blue() -> AllianceFlip {
+        AllianceFlip::for_alliance(false)
+    }
+
+    fn observation(camera: &str, captured: f64) -> Observation {
+        Observation {
+            camera: camera.to_owned(),
+            captured,
+            tags: vec![Sighting {
+                id: 7,
+                distance: 2.5,
+                ambiguity: 0.1,
+            }],
+            pose: Estimate {
+                x: 1.5,
+                y: 5.5,
+                theta: 180.,
+                std_devs: [0.05, 0.05, 5.],
+            },
+        }
+    }
+
+    #[test]
+    fn captured_on_our_clock() {
+        let mut vision = Vision::default();
+        let received = Instant::now();
+        let batch = Batch {
+            sent: 100.,
+            observations: vec![observation("front", 99.9), observation("back", 99.95)],
+        };
+
+        assert_eq!(vision.ingest(batch, received, &blue()), Ok(2));
+        assert_eq!(vision.cameras().count(), 2);
+
+        let front = &vision.camera("front").unwrap()[0];
+        let latency = received - front.captured;
+        assert!((latency.as_secs_f64() - 0.1).abs() < 1e-6);
+        assert_eq!(vision.latest().unwrap(), &vision.camera("back").unwrap()[0]);
+    }
+
+    #[test]
+    fn red_lands_in_our_odometry() {
+        let mut vision = Vision::default();
+        let batch = Batch {
+            sent: 100.,
+            observations: vec![observation("front", 99.9)],
+        };
+        let red = AllianceFlip::for_alliance(true);
+        vision.ingest(batch, Instant::now(), &red).unwrap();
+
+        // 1.5m from the blue wall is a long way from ours
+        let measurement = vision.latest().unwrap();
+        assert!((measurement.position.x - (LAYOUT.length - 1.5)).abs() < 1e-9);
+        assert!((measurement.position.y - (RED_REFLECTION_METERS - 5.5)).abs() < 1e-9);
+        assert!(measurement.heading.get::<degree>().abs() < 1e-9);
+    }
+
+    #[test]
+    fn rejects_bad_batches_whole() {
+        let mut vision = Vision::default();
+        let bad: [fn(&mut Observation); 9] = [
+            |o| o.tags.clear(),
+            |o| o.tags[0].id = 17,
+            |o| o.tags[0].ambiguity = 1.5,
+            |o| o.tags[0].distance = f64::NAN,
+            |o| o.pose.x = -3.,
+            |o| o.pose.std_devs[2] = 0.,
+            |o| o.captured = 101.,
+            |o| o.captured = 10.,
+            |o| o.camera.clear(),
+        ];
+
+        for (i, break_it) in bad.iter().enumerate() {
+            let mut broken = observation("front", 99.9);
+            break_it(&mut broken);
+            let batch = Batch {
+                sent: 100.,
+                observations: vec![observation("front", 99.9), broken],
+            };
+
+            let err = vision.ingest(batch, Instant::now(), &blue()).unwrap_err();
+            assert!(err.starts_with("observation 1"), "{i}: {err}");
+        }
+        assert!(vision.latest().is_none());
+    }
+
+    #[test]
+    fn ring_buffer_keeps_newest_in_order() {
+        let mut vision = Vision::default();
+        let start = Instant::now();
+
+        // newest first, as if the batches came in backwards
+        for i in 0..BUFFER_LENGTH * 2 {
+            let batch = Batch {
+                sent: 1000.,
+                observations: vec![observation("front", 1000. - 0.001 * i as f64)],
+            };
+            vision.ingest(batch, start, &blue()).unwrap();
+        }
+
+        let front = vision.camera("front").unwrap();
+        assert_eq!(front.len(), BUFFER_LENGTH);
+        assert!(front
+            .iter()
+            .zip(front.iter().skip(1))
+            .all(|(a, b)| a.captured <= b.captured));
+        assert_eq!(front.back().unwrap().captured, start);
+
+        let cutoff = start - Duration::from_secs_f64(0.0105);
+        assert_eq!(vision.since(cutoff).len(), 11);
+    }
+}
@@ -0,0 +1,155 @@
+use std::{
+    cell::Cell,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// A stall the watchdog caught, and what the loop was doing when it started
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stall {
+    pub task: &'static str,
+    /// seconds since the last feed when it tripped
+    pub after: f64,
+}
+
+struct Shared {
+    fed: Mutex<Instant>,
+    task: Mutex<&'static str>,
+    tripped: AtomicBool,
+    stalls: Mutex<Vec<Stall>>,
+}
+
+/// Puts every motor in neutral from its own thread if the control loop
+/// stops feeding it
+///
+/// The control loop runs on one thread with everything else, so a blocked
+/// lock or a slow call stops it from ever getting to the code that would
+/// notice
+pub struct Watchdog {
+    shared: Arc<Shared>,
+    /// first feed after the last stall
+    recovered: Cell<Option<Instant>>,
+}
+
+impl Watchdog {
+    pub fn spawn(timeout: Duration, neutral: impl Fn() + Send + 'static) -> Self {
+        let shared = Arc::new(Shared {
+            fed: Mutex::new(Instant::now()),
+            task: Mutex::new("starting"),
+            tripped: AtomicBool::new(false),
+            stalls: Mutex::new(Vec::new()),
+        });
+
+        let watched = shared.clone();
+        thread::Builder::new()
+            .name("watchdog".to_owned())
+            .spawn(move || loop {
+                thread::sleep(timeout / 4);
+
+                let since = watched
+                    .fed
+                    .lock()
+                    .map_or(Duration::ZERO, |fed| fed.elapsed());
+                if since < timeout || watched.tripped.swap(true, Ordering::SeqCst) {
+                    continue;
+                }
+
+                let task = watched.task.lock().map_or("unknown", |task| *task);
+                println!(
+                    "watchdog: loop stalled {:.0}ms in {task}, stopping motors",
+                    since.as_secs_f64() * 1000.
+                );
+                neutral();
+
+                if let Ok(mut stalls) = watched.stalls.lock() {
+                    stalls.push(Stall {
+                        task,
+                        after: since.as_secs_f64(),
+                    });
+                }
+            })
+            .expect("couldn't start the watchdog thread");
+
+        Self {
+            shared,
+            recovered: Cell::new(None),
+        }
+    }
+
+    /// call every loop
+    pub fn feed(&self) {
+        if let Ok(mut fed) = self.shared.fed.lock() {
+            *fed = Instant::now();
+        }
+        if self.shared.tripped.swap(false, Ordering::SeqCst) {
+            self.recovered.set(Some(Instant::now()));
+        }
+    }
+
+    /// how long the loop has been fed since it last stalled, None if it never has
+    pub fn recovered_for(&self) -> Option<Duration> {
+        self.recovered.get().map(|recovered| recovered.elapsed())
+    }
+
+    /// what the loop is about to do, blamed if it stalls
+    pub fn enter(&self, task: &'static str) {
+        if let Ok(mut current) = self.shared.task.lock() {
+            *current = task;
+        }
+    }
+
+    /// stalls caught since the last call
+    pub fn take_stalls(&self) -> Vec<Stall> {
+        self.shared
+            .stalls
+            .lock()
+            .map_or(Vec::new(), |mut stalls| std::mem::take(&mut *stalls))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    };
+
+    use super::Watchdog;
+
+    #[test]
+    fn trips_once_per_stall() {
+        let stopped = Arc::new(AtomicUsize::new(0));
+        let counter = stopped.clone();
+        let watchdog = Watchdog::spawn(Duration::from_millis(40), move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        for _ in 0..10 {
+            watchdog.enter("control");
+            watchdog.feed();
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(stopped.load(Ordering::SeqCst), 0);
+
+        watchdog.enter("power");
+        thread::sleep(Duration::from_millis(200));
+        assert_eq!(stopped.load(Ordering::SeqCst), 1);
+
+        let stalls = watchdog.take_stalls();
+        assert_eq!(stalls.len(), 1);
+        assert_eq!(stalls[0].task, "power");
+        assert!(watchdog.take_stalls().is_empty());
+
+        assert!(watchdog.recovered_for().is_none());
+        watchdog.feed();
+        assert!(watchdog.recovered_for().is_some());
+    }
+}
@@ -0,0 +1,125 @@
+//! Calls into `frc.robot.Wrapper` for what frcrs doesn't bind
+//!
+//! Everything here is a JNI call, so only valid from a thread the JVM knows
+//! about. A failed call is logged and reads as the default
+
+use std::any::Any;
+
+use j4rs::{InvocationArg, Jvm};
+use serde::de::DeserializeOwned;
+
+const WRAPPER: &str = "frc.robot.Wrapper";
+
+fn call<T: DeserializeOwned + Any>(method: &str, args: &[InvocationArg]) -> Option<T> {
+    let result = Jvm::attach_thread()
+        .and_then(|jvm| jvm.invoke_static(WRAPPER, method, args).map(|i| (jvm, i)))
+        .and_then(|(jvm, instance)| jvm.to_rust(instance));
+
+    match result {
+        Ok(value) => Some(value),
+        Err(e) => {
+            println!("Wrapper.{method} failed: {e}");
+            None
+        }
+    }
+}
+
+fn run(method: &str, args: &[InvocationArg]) {
+    let result = Jvm::attach_thread().and_then(|jvm| jvm.invoke_static(WRAPPER, method, args));
+    if let Err(e) = result {
+        println!("Wrapper.{method} failed: {e}");
+    }
+}
+
+fn int(value: i32) -> InvocationArg {
+    InvocationArg::try_from(value)
+        .and_then(InvocationArg::into_primitive)
+        .expect("an int is always a valid argument")
+}
+
+fn double(value: f64) -> InvocationArg {
+    InvocationArg::try_from(value)
+        .and_then(InvocationArg::into_primitive)
+        .expect("a double is always a valid argument")
+}
+
+/// volts at the roboRIO, none if it couldn't be read
+pub fn battery_voltage() -> Option<f64> {
+    call("batteryVoltage", &[])
+}
+
+/// the roboRIO has cut motor output to stay alive
+pub fn browned_out() -> bool {
+    call("isBrownedOut", &[]).unwrap_or(false)
+}
+
+/// amps on every pdh channel, indexed by channel
+pub fn pdh_currents() -> Option<Vec<f64>> {
+    call("pdhCurrents", &[])
+}
+
+/// volts at the pdh input
+pub fn pdh_voltage() -> Option<f64> {
+    call("pdhVoltage", &[])
+}
+
+/// amps
+pub fn pdh_total_current() -> Option<f64> {
+    call("pdhTotalCurrent", &[])
+}
+
+/// celsius
+pub fn pdh_temperature() -> Option<f64> {
+    call("pdhTemperature", &[])
+}
+
+/// joules since the last reset
+pub fn pdh_total_energy() -> Option<f64> {
+    call("pdhTotalEnergy", &[])
+}
+
+pub fn pdh_reset_total_energy() {
+    run("pdhResetTotalEnergy", &[]);
+}
+
+/// stator current limit in amps, for the talon with CAN id `id` on can0
+pub fn talon_current_limit(id: i32, amps: f64) {
+    run("talonCurrentLimit", &[int(id), double(amps)]);
+}
+
+/// degrees of side to side tilt from the navx
+pub fn navx_roll() -> Option<f64> {
+    call("navxRoll", &[])
+}
+
+pub fn navx_connected() -> bool {
+    call("navxConnected", &[]).unwrap_or(false)
+}
+
+/// false if it isn't answering on can0
+pub fn talon_connected(id: i32) -> bool {
+    call("talonConnected", &[int(id)]).unwrap_or(false)
+}
+
+/// the fault bitfield, 0 if nothing's wrong
+pub fn talon_faults(id: i32) -> Option<i32> {
+    call("talonFaults", &[int(id)])
+}
+
+/// celsius
+pub fn talon_temperature(id: i32) -> Option<f64> {
+    call("talonTemperature", &[int(id)])
+}
+
+/// stator amps
+pub fn talon_current(id: i32) -> Option<f64> {
+    call("talonCurrent", &[int(id)])
+}
+
+pub fn cancoder_connected(id: i32) -> bool {
+    call("cancoderConnected", &[int(id)]).unwrap_or(false)
+}
+
+pub fn cancoder_faults(id: i32) -> Option<i32> {
+    call("cancoderFaults", &[int(id)])
+}
@@ -19,28 +19,96 @@ async function post(endpoint: string, data: string): Promise<void> {
 export interface Auto {
     Picker: {
         options: string[],
-        selected: number
+        labels: string[],
+        selected: string
     }
 }
 
+export interface Confirmation {
+    id: string,
+    name: string,
+    alliance: "red" | "blue",
+    start: {x: number, y: number, theta: number} | null,
+    handling: string,
+}
+
+export interface Readiness {
+    go: boolean,
+    checks: {name: string, go: boolean, detail: string}[],
+}
+
+export interface Alarm {
+    device: string,
+    problem: string,
+    severity: "Warning" | "Error" | "Critical",
+    value: number | null,
+}
+
+export interface TaskStats {
+    runs: number,
+    overruns: number,
+    skipped: number,
+    last: number,
+    max: number,
+    total: number,
+    rate: number,
+    histogram: {counts: number[]},
+}
+
+export interface Timing {
+    buckets: number[],
+    tasks: {[name: string]: TaskStats},
+}
+
+export interface Crash {
+    time: number,
+    uptime: number,
+    message: string,
+    location: string | null,
+}
+
+const severityColors = {
+    Warning: "gold",
+    Error: "orange",
+    Critical: "red",
+}
+
 export default function Home() {
     const [autos, setAutos] = useState<Auto | null>(null)
-    const [selected, setSelected] = useState(0)
+    const [selected, setSelected] = useState("")
+    const [confirmation, setConfirmation] = useState<Confirmation | null>(null)
+    const [readiness, setReadiness] = useState<Readiness | null>(null)
     const [hz, setHz] = useState(0)
     const [load, setLoad] = useState(0)
     const [flywheelState, setFlywheelState] = useState(false)
+    const [alarms, setAlarms] = useState<Alarm[]>([])
+    const [timing, setTiming] = useState<Timing | null>(null)
+    const [crashes, setCrashes] = useState<Crash[]>([])
 
     useEffect(() => {
         setInterval(() => get("get/auto chooser").then(value => {
             setAutos(JSON.parse(value))
             setSelected(JSON.parse(value)["Picker"]["selected"])
         }), 750)
+        setInterval(() => get("get/auto confirmation").then(value => {
+            setConfirmation(JSON.parse(JSON.parse(value)["Text"]))
+        }).catch(() => setConfirmation(null)), 750)
+        setInterval(() => get("get/readiness").then(value => {
+            setReadiness(JSON.parse(JSON.parse(value)["Text"]))
+        }).catch(() => setReadiness(null)), 500)
 
         setInterval(() => get("get/loop rate (hz)").then(value => setHz(Number.parseFloat(JSON.parse(value)["Number"]))), 500)
         setInterval(() => get("get/rio load").then(value => setLoad(Number.parseFloat(JSON.parse(value)["Number"]))), 200)
         setInterval(() => get("get/flywheel state").then(value => {
             setFlywheelState(JSON.parse(value)["Bool"]);
         }), 250)
+        setInterval(() => get("get/alarms").then(value => {
+            setAlarms(JSON.parse(JSON.parse(value)["Text"]))
+        }).catch(() => setAlarms([])), 1000)
+        setInterval(() => get("get/task timing").then(value => {
+            setTiming(JSON.parse(JSON.parse(value)["Text"]))
+        }).catch(() => setTiming(null)), 1000)
+        setInterval(() => get("crashes").then(value => setCrashes(JSON.parse(value))).catch(() => setCrashes([])), 5000)
     }, []);
 
     //@ts-ignore
@@ -54,6 +122,7 @@ export default function Home() {
             return {
                 Picker: {
                     options: prevState.Picker.options,
+                    labels: prevState.Picker.labels,
                     selected: e.target.value
                 }
             }
@@ -73,16 +142,67 @@ export default function Home() {
               <div style={{width: Math.min(load*100, 100)+"%", height: "100%", background: "lightgreen", borderRadius: "5px", transitionDuration: "0.8s", transitionProperty: "width"}}></div> 
           </div>
           <a>{`Flywheel State: ${flywheelState}`}</a>
+          <div className="flex flex-col gap-1">
+              {alarms.map((alarm, idx) => (
+                  <a key={idx} style={{color: severityColors[alarm.severity]}}>
+                      {`${alarm.severity}: ${alarm.device} ${alarm.problem}` + (alarm.value !== null ? ` (${alarm.value.toFixed(1)})` : "")}
+                  </a>
+              ))}
+          </div>
+          {timing && Object.entries(timing.tasks).map(([name, stats]) => {
+              const most = Math.max(...stats.histogram.counts, 1)
+              return (
+                  <div key={name} className="flex flex-col items-center">
+                      <a>{`${name}: ${stats.rate.toFixed(0)}hz, ${stats.last.toFixed(2)}ms (max ${stats.max.toFixed(2)}ms), ${stats.overruns} overruns`}</a>
+                      <div className="flex flex-row items-end gap-1" style={{height: "40px"}}>
+                          {stats.histogram.counts.map((count, idx) => (
+                              <div key={idx} title={idx < timing.buckets.length ? `<= ${timing.buckets[idx]}ms: ${count}` : `> ${timing.buckets[idx - 1]}ms: ${count}`}
+                                   style={{width: "12px", height: (count / most * 100) + "%", background: idx < timing.buckets.length ? "lightgreen" : "red"}}></div>
+                          ))}
+                      </div>
+                  </div>
+              )
+          })}
+          {crashes.length > 0 && (
+              <div className="flex flex-col gap-1">
+                  <a>Recent crashes</a>
+                  {crashes.map(crash => (
+                      <a key={crash.time} style={{color: "red"}}>
+                          {`${new Date(crash.time).toLocaleString()} after ${crash.uptime.toFixed(0)}s: ${crash.message}` + (crash.location ? ` (${crash.location})` : "")}
+                      </a>
+                  ))}
+              </div>
+          )}
+          {confirmation && (
+              <div className="flex flex-col items-center" style={{color: confirmation.alliance}}>
+                  <a>{`Auto: ${confirmation.name} (${confirmation.id})`}</a>
+                  <a>{confirmation.start
+                      ? `Start: x ${confirmation.start.x.toFixed(2)}m, y ${confirmation.start.y.toFixed(2)}m, ${confirmation.start.theta.toFixed(0)}°`
+                      : "Start: anywhere"}</a>
+                  <a>{`${confirmation.alliance} alliance, ${confirmation.handling}`}</a>
+              </div>
+          )}
+          {readiness && (
+              <div className="flex flex-col items-center">
+                  <a style={{fontWeight: "bold", color: readiness.go ? "lightgreen" : "red"}}>{readiness.go ? "GO" : "NO GO"}</a>
+                  {readiness.checks.map(check => (
+                      <a key={check.name} style={{color: check.go ? "lightgreen" : "red"}}>
+                          {`${check.go ? "✔" : "✘"} ${check.name}: ${check.detail}`}
+                      </a>
+                  ))}
+              </div>
+          )}
           <div className="flex flex-col gap-4 w-full">
 
               {autos.Picker.options.map((auto, idx) => {
                   return (
-                      <Button className="w-72" key={idx} onPress={() => post("set/auto chooser", JSON.stringify({
+                      <Button className="w-72" key={auto} onPress={() => post("set/auto chooser", JSON.stringify({
                           Picker: {
                               options: autos?.Picker.options,
-                              selected: idx.toString(),
+                              labels: autos?.Picker.labels,
+                              selected: auto,
                           }
-                      }))} variant="solid" style={(autos.Picker.selected == idx) ? {background:  "green"}: {}}>{auto}</Button>
+                      }))} variant="solid" style={(autos.Picker.selected == auto) ? {background:  "green"}: {}}>{autos.Picker.labels[idx] ?? auto}</Button>
                   )
               })}
           </div>